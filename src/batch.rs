@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde_json::Value;
+
+use crate::json::insert::insert_under_key;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::remove::remove_key_at_path;
+use crate::json::rename::{ConflictStrategy, RenameError, rename_key_at_path};
+use crate::json::replace::replace_value_at_key;
+use crate::json::upsert::{Upserted, upsert_value_at_key};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Rename,
+    Remove,
+    Add,
+    Replace,
+    Upsert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    Added,
+    Replaced,
+    Removed,
+    Renamed,
+    Unchanged,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Outcome::Added => "added",
+            Outcome::Replaced => "replaced",
+            Outcome::Removed => "removed",
+            Outcome::Renamed => "renamed",
+            Outcome::Unchanged => "unchanged",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BatchEntry {
+    pub path: KeyPath,
+    pub op: Operation,
+    pub to: Option<KeyPath>,
+    pub values: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct RawEntry {
+    #[serde(default = "default_operation")]
+    op: Operation,
+    to: Option<String>,
+    #[serde(default)]
+    values: HashMap<String, Value>,
+}
+
+fn default_operation() -> Operation {
+    Operation::Add
+}
+
+/// Entries in file order. A map would keep only the last of duplicated paths.
+struct RawEntries(Vec<(String, RawEntry)>);
+
+impl<'de> Deserialize<'de> for RawEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = RawEntries;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of key paths to batch entries")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawEntries, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(RawEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// Loads batch file. Format is picked by extension: `.json`, `.yaml`/`.yml`
+/// or `.csv`. Entries are ordered so renames run first, then removals, then
+/// writes, which lets one batch rename a key and update its value.
+pub fn load_batch<P: AsRef<Path>>(path: P) -> Result<Vec<BatchEntry>, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| format!("Can't read {:?}: {}", path, e))?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    parse_batch(&content, extension)
+}
+
+fn parse_batch(content: &str, extension: &str) -> Result<Vec<BatchEntry>, String> {
+    let RawEntries(raw) = match extension {
+        "json" => serde_json::from_str(content).map_err(|e| e.to_string())?,
+        "yaml" | "yml" => serde_yaml::from_str(content).map_err(|e| e.to_string())?,
+        "csv" => parse_csv(content)?,
+        _ => {
+            return Err(format!(
+                "Unsupported batch file extension '{}'! Use json, yaml or csv.",
+                extension
+            ));
+        }
+    };
+
+    let mut entries: Vec<BatchEntry> = Vec::new();
+    for (path, entry) in raw {
+        let to = entry.to.as_deref().map(KeyPath::parse).transpose()?;
+        if entry.op == Operation::Rename && to.is_none() {
+            return Err(format!("Rename of '{}' is missing 'to' key!", path));
+        }
+        let path = KeyPath::parse(&path)?;
+        if entries.iter().any(|entry| entry.path == path) {
+            return Err(format!("Key '{}' appears more than once in batch!", path));
+        }
+        entries.push(BatchEntry {
+            path,
+            op: entry.op,
+            to,
+            values: entry.values,
+        });
+    }
+    entries.sort_by_key(|entry| entry.op);
+
+    Ok(entries)
+}
+
+/// Reads CSV with `path`, `op` and `to` columns, every other column is a
+/// locale. Empty locale cells are skipped.
+fn parse_csv(content: &str) -> Result<RawEntries, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let mut entries = Vec::new();
+
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let mut path = None;
+        let mut entry = RawEntry {
+            op: default_operation(),
+            to: None,
+            values: HashMap::new(),
+        };
+
+        for (header, cell) in headers.iter().zip(record.iter()) {
+            match header {
+                "path" => path = Some(cell.to_string()),
+                "op" if !cell.is_empty() => {
+                    entry.op = serde_json::from_value(Value::String(cell.to_string()))
+                        .map_err(|_| format!("Unknown operation '{}'!", cell))?;
+                }
+                "to" if !cell.is_empty() => entry.to = Some(cell.to_string()),
+                "op" | "to" => {}
+                locale if !cell.is_empty() => {
+                    entry
+                        .values
+                        .insert(locale.to_string(), Value::String(cell.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        let path = path.ok_or_else(|| "CSV batch file is missing 'path' column!".to_string())?;
+        entries.push((path, entry));
+    }
+
+    Ok(RawEntries(entries))
+}
+
+/// Applies entry to file of given locale. Returns `None` when entry has
+/// nothing to do for this locale.
+pub fn apply_entry(
+    json: &mut Value,
+    locale: &str,
+    entry: &BatchEntry,
+) -> Result<Option<Outcome>, String> {
+    let segments = entry.path.segments();
+    let (path, key) = entry.path.parent_and_key()?;
+    match entry.op {
+        Operation::Remove => {
+            if get_value_at_path(json, &segments).is_none() {
+                return Ok(None);
+            }
+            remove_key_at_path(json, &path, key)?;
+            Ok(Some(Outcome::Removed))
+        }
+        Operation::Rename => {
+            let to = entry.to.clone().unwrap_or_default();
+            match rename_key_at_path(json, &entry.path, &to, ConflictStrategy::Fail) {
+                Ok(()) => Ok(Some(Outcome::Renamed)),
+                Err(RenameError::NotFound(_)) => Ok(None),
+                Err(error) => Err(error.to_string()),
+            }
+        }
+        Operation::Add | Operation::Replace | Operation::Upsert => {
+            let Some(value) = entry.values.get(locale).cloned() else {
+                return Ok(None);
+            };
+            match entry.op {
+                Operation::Add => {
+                    insert_under_key(json, &path, key, value)?;
+                    Ok(Some(Outcome::Added))
+                }
+                Operation::Replace => {
+                    replace_value_at_key(json, &path, key, value)?;
+                    Ok(Some(Outcome::Replaced))
+                }
+                _ => match upsert_value_at_key(json, &path, key, value)? {
+                    Upserted::Created => Ok(Some(Outcome::Added)),
+                    Upserted::Updated => Ok(Some(Outcome::Replaced)),
+                    Upserted::Unchanged => Ok(Some(Outcome::Unchanged)),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod parse_batch {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_load_json_batch_with_renames_first() {
+        let content = r#"{
+            "greeting": { "values": { "en": "Hi", "sv": "Hej" } },
+            "old": { "op": "rename", "to": "new" }
+        }"#;
+
+        let result = parse_batch(content, "json").unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].op, Operation::Rename);
+        assert_eq!(result[0].to, Some(KeyPath::parse("new").unwrap()));
+        assert_eq!(result[1].op, Operation::Add);
+        assert_eq!(result[1].values.get("sv"), Some(&json!("Hej")));
+    }
+
+    #[test]
+    fn should_load_yaml_batch() {
+        let content = "pax.rooms:\n  op: replace\n  values:\n    en: Rooms\n    sv: Rum\n";
+
+        let result = parse_batch(content, "yaml").unwrap();
+
+        assert_eq!(result[0].path, KeyPath::parse("pax.rooms").unwrap());
+        assert_eq!(result[0].op, Operation::Replace);
+        assert_eq!(result[0].values.get("en"), Some(&json!("Rooms")));
+    }
+
+    #[test]
+    fn should_load_csv_batch_and_skip_empty_cells() {
+        let content =
+            "path,op,to,en,sv\npax.rooms,upsert,,Rooms,\npax.kids,rename,pax.children,,\n";
+
+        let result = parse_batch(content, "csv").unwrap();
+
+        assert_eq!(result[0].op, Operation::Rename);
+        assert_eq!(result[0].to, Some(KeyPath::parse("pax.children").unwrap()));
+        assert_eq!(result[1].op, Operation::Upsert);
+        assert_eq!(result[1].values.len(), 1);
+        assert_eq!(result[1].values.get("en"), Some(&json!("Rooms")));
+    }
+
+    #[test]
+    fn should_return_err_if_rename_has_no_destination() {
+        let content = r#"{ "old": { "op": "rename" } }"#;
+
+        let result = parse_batch(content, "json");
+
+        assert_eq!(result.unwrap_err(), "Rename of 'old' is missing 'to' key!");
+    }
+
+    #[test]
+    fn should_return_err_if_path_is_duplicated() {
+        let json = r#"{ "pax.rooms": { "op": "remove" }, "pax.rooms": { "op": "add" } }"#;
+        let csv = "path,en\npax.rooms,Rooms\npax.rooms,Room\n";
+
+        for (content, extension) in [(json, "json"), (csv, "csv")] {
+            let result = parse_batch(content, extension);
+
+            assert_eq!(
+                result.unwrap_err(),
+                "Key 'pax.rooms' appears more than once in batch!"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod apply_entry {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(path: &str, op: Operation, values: Value) -> BatchEntry {
+        BatchEntry {
+            path: KeyPath::parse(path).unwrap(),
+            op,
+            to: None,
+            values: serde_json::from_value(values).unwrap(),
+        }
+    }
+
+    #[test]
+    fn should_upsert_existing_and_missing_keys() {
+        let mut data = json!({ "a": "old" });
+        let expected = json!({ "a": "new_a", "b": "new_b" });
+
+        let replaced = apply_entry(
+            &mut data,
+            "en",
+            &entry("a", Operation::Upsert, json!({ "en": "new_a" })),
+        );
+        let added = apply_entry(
+            &mut data,
+            "en",
+            &entry("b", Operation::Upsert, json!({ "en": "new_b" })),
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(replaced.unwrap(), Some(Outcome::Replaced));
+        assert_eq!(added.unwrap(), Some(Outcome::Added));
+    }
+
+    #[test]
+    fn should_report_unchanged_upsert() {
+        let mut data = json!({ "a": "value" });
+
+        let result = apply_entry(
+            &mut data,
+            "en",
+            &entry("a", Operation::Upsert, json!({ "en": "value" })),
+        );
+
+        assert_eq!(data, json!({ "a": "value" }));
+        assert_eq!(result.unwrap(), Some(Outcome::Unchanged));
+    }
+
+    #[test]
+    fn should_skip_locale_without_value() {
+        let mut data = json!({});
+
+        let result = apply_entry(
+            &mut data,
+            "sv",
+            &entry("a", Operation::Add, json!({ "en": "value" })),
+        );
+
+        assert_eq!(data, json!({}));
+        assert_eq!(result.unwrap(), None);
+    }
+
+    #[test]
+    fn should_return_err_if_added_key_exists() {
+        let mut data = json!({ "a": "old" });
+
+        let result = apply_entry(
+            &mut data,
+            "en",
+            &entry("a", Operation::Add, json!({ "en": "new" })),
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            "Key 'a' already exists at the target path! Use REPLACE command instead."
+        );
+    }
+
+    #[test]
+    fn should_rename_and_remove_keys() {
+        let mut data = json!({ "a": "value", "b": "value" });
+        let mut rename = entry("a", Operation::Rename, json!({}));
+        rename.to = Some(KeyPath::parse("c").unwrap());
+
+        let renamed = apply_entry(&mut data, "en", &rename);
+        let removed = apply_entry(&mut data, "en", &entry("b", Operation::Remove, json!({})));
+
+        assert_eq!(data, json!({ "c": "value" }));
+        assert_eq!(renamed.unwrap(), Some(Outcome::Renamed));
+        assert_eq!(removed.unwrap(), Some(Outcome::Removed));
+    }
+}
@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::git::{self, Commit};
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+
+/// Commit that set key to value. `None` value means the key was removed.
+#[derive(Debug, PartialEq)]
+pub struct ValueChange {
+    pub commit: Commit,
+    pub value: Option<Value>,
+}
+
+/// Returns changes of value at path in file, newest first. The first change
+/// is the one that set the current value.
+pub fn blame_key(file: &Path, path: &KeyPath) -> Result<Vec<ValueChange>, String> {
+    let segments = path.segments();
+    let mut history = Vec::new();
+
+    for commit in git::file_history(file)? {
+        // File may be missing or broken at some revisions, key is missing there.
+        let value = git::read_file_at_revision(&commit.hash, file)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|json| get_value_at_path(&json, &segments).cloned());
+        history.push((commit, value));
+    }
+
+    Ok(find_value_changes(history))
+}
+
+/// Keeps only commits, where value differs from the one in the previous
+/// (older) commit. History is ordered newest first.
+fn find_value_changes(history: Vec<(Commit, Option<Value>)>) -> Vec<ValueChange> {
+    let older_values: Vec<Option<Value>> = history
+        .iter()
+        .skip(1)
+        .map(|(_, value)| value.clone())
+        .chain([None])
+        .collect();
+
+    history
+        .into_iter()
+        .zip(older_values)
+        .filter(|((_, value), older)| value != older)
+        .map(|((commit, value), _)| ValueChange { commit, value })
+        .collect()
+}
+
+#[cfg(test)]
+mod find_value_changes {
+    use super::*;
+    use serde_json::json;
+
+    fn commit(hash: &str) -> Commit {
+        Commit {
+            hash: hash.to_string(),
+            author: "Author".to_string(),
+            date: "2026-01-01".to_string(),
+        }
+    }
+
+    #[test]
+    fn should_keep_commits_changing_value() {
+        let history = vec![
+            (commit("d"), Some(json!("Rooms"))),
+            (commit("c"), Some(json!("Room | Rooms"))),
+            (commit("b"), Some(json!("Room | Rooms"))),
+            (commit("a"), None),
+        ];
+
+        let result = find_value_changes(history);
+
+        assert_eq!(
+            result,
+            vec![
+                ValueChange {
+                    commit: commit("d"),
+                    value: Some(json!("Rooms"))
+                },
+                ValueChange {
+                    commit: commit("b"),
+                    value: Some(json!("Room | Rooms"))
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_removal() {
+        let history = vec![(commit("b"), None), (commit("a"), Some(json!("Rooms")))];
+
+        let result = find_value_changes(history);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].value, None);
+        assert_eq!(result[1].value, Some(json!("Rooms")));
+    }
+}
@@ -0,0 +1,750 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::files;
+use crate::json::compare::{JsonDiff, get_missing_paths};
+use crate::json::insert::insert_tree_under_key;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::{get_json_paths, get_node_paths};
+use crate::json::remove::remove_key_at_path;
+use crate::json::rename::{ConflictStrategy, RenameError, rename_key_at_path};
+use crate::json::replace::replace_value_at_key;
+use crate::json::shape::fix_shape_conflicts;
+use crate::json::upsert::{Upserted, upsert_tree_under_key, upsert_value_at_key};
+
+struct Locale {
+    /// File name without extension, e.g. `en`.
+    name: String,
+    file: PathBuf,
+    json: Value,
+}
+
+/// Translation files of one directory, one locale per file. Changes are made
+/// in memory and written by [`Catalog::save`]. An operation failing in any
+/// locale changes none of them.
+pub struct Catalog {
+    /// Sorted by name.
+    locales: Vec<Locale>,
+    changed: BTreeSet<usize>,
+}
+
+impl Catalog {
+    /// Loads every `.json` file in directory.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+        let io_error = |source| Error::Io {
+            file: dir.to_path_buf(),
+            source,
+        };
+
+        let mut locales = Vec::new();
+        for entry in fs::read_dir(dir).map_err(io_error)? {
+            let file = entry.map_err(io_error)?.path();
+            if !file.is_file() || !files::is_json_file(&file) {
+                continue;
+            }
+
+            let content = fs::read_to_string(&file).map_err(|source| Error::Io {
+                file: file.clone(),
+                source,
+            })?;
+            let json = serde_json::from_str(&content).map_err(|source| Error::Parse {
+                file: file.clone(),
+                source,
+            })?;
+            locales.push((file, json));
+        }
+
+        Ok(Catalog::new(locales))
+    }
+
+    /// Creates catalog from already loaded files. Nothing is read from disk,
+    /// `save` writes to the given paths.
+    pub fn new(locales: impl IntoIterator<Item = (PathBuf, Value)>) -> Self {
+        let mut locales: Vec<Locale> = locales
+            .into_iter()
+            .map(|(file, json)| Locale {
+                name: file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                file,
+                json,
+            })
+            .collect();
+        locales.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Catalog {
+            locales,
+            changed: BTreeSet::new(),
+        }
+    }
+
+    /// Locale names, sorted.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.locales.iter().map(|locale| locale.name.as_str())
+    }
+
+    /// Locale the others are translated from: the requested one, given as
+    /// locale or file name, or else the default, see [`default_reference`].
+    pub fn reference(&self, requested: Option<&str>) -> Result<&str, Error> {
+        let index = match requested {
+            Some(requested) => {
+                let name = Path::new(requested)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy())
+                    .unwrap_or_default();
+                self.index(&name)
+                    .map_err(|_| Error::LocaleNotFound(requested.to_string()))?
+            }
+            None => default_reference(
+                self.locales
+                    .iter()
+                    .map(|locale| get_json_paths(&locale.json).len()),
+            )
+            .ok_or(Error::NoLocales)?,
+        };
+        Ok(&self.locales[index].name)
+    }
+
+    /// Locales changed since the last save.
+    pub fn changed(&self) -> impl Iterator<Item = &str> {
+        self.changed
+            .iter()
+            .map(|&index| self.locales[index].name.as_str())
+    }
+
+    pub fn file(&self, locale: &str) -> Result<&Path, Error> {
+        Ok(&self.locales[self.index(locale)?].file)
+    }
+
+    pub fn json(&self, locale: &str) -> Result<&Value, Error> {
+        Ok(&self.locales[self.index(locale)?].json)
+    }
+
+    pub fn get(&self, locale: &str, key: &KeyPath) -> Result<Option<&Value>, Error> {
+        Ok(get_value_at_path(self.json(locale)?, &key.segments()))
+    }
+
+    /// Paths of values present in any locale.
+    pub fn paths(&self) -> BTreeSet<KeyPath> {
+        self.locales
+            .iter()
+            .flat_map(|locale| get_json_paths(&locale.json))
+            .collect()
+    }
+
+    /// Paths of every key present in any locale, including keys holding
+    /// objects. Selectors are matched against these.
+    pub fn node_paths(&self) -> BTreeSet<KeyPath> {
+        self.locales
+            .iter()
+            .flat_map(|locale| get_node_paths(&locale.json))
+            .collect()
+    }
+
+    /// Returns file and content of every locale, sorted by locale name.
+    pub fn into_files(self) -> Vec<(PathBuf, Value)> {
+        self.locales
+            .into_iter()
+            .map(|locale| (locale.file, locale.json))
+            .collect()
+    }
+
+    /// Adds value under key in every given locale. Object values are merged
+    /// into objects already present, but no existing value is overwritten.
+    pub fn add(
+        &mut self,
+        key: &KeyPath,
+        values: impl IntoIterator<Item = (String, Value)>,
+    ) -> Result<(), Error> {
+        split(key)?;
+        self.apply(values, |json, value| {
+            insert_tree_under_key(json, key, value)
+        })
+    }
+
+    /// Replaces value of key in every given locale. Key has to exist in all
+    /// of them.
+    pub fn replace(
+        &mut self,
+        key: &KeyPath,
+        values: impl IntoIterator<Item = (String, Value)>,
+    ) -> Result<(), Error> {
+        let (parent, last) = split(key)?;
+        self.apply(values, |json, value| {
+            if get_value_at_path(json, &key.segments()).is_none() {
+                return Err(vec![format!("Key '{}' not found!", key)]);
+            }
+            replace_value_at_key(json, &parent, last, value).map_err(|e| vec![e])
+        })
+    }
+
+    /// Creates or overwrites key in every given locale. Object values are
+    /// written key by key. Returns the outcome for every written key.
+    pub fn upsert(
+        &mut self,
+        key: &KeyPath,
+        values: impl IntoIterator<Item = (String, Value)>,
+    ) -> Result<Vec<(String, KeyPath, Upserted)>, Error> {
+        let (parent, last) = split(key)?;
+        let values = values.into_iter().collect::<Vec<_>>();
+        let mut results = Vec::new();
+        self.apply(values.clone(), |json, value| {
+            results.push(upsert_tree_under_key(json, &parent, last, value).map_err(|e| vec![e])?);
+            Ok(())
+        })?;
+
+        // Locales are changed in catalog order.
+        let mut locales = values
+            .into_iter()
+            .map(|(locale, _)| self.index(&locale).map(|index| (index, locale)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        locales.sort();
+        Ok(locales
+            .into_iter()
+            .zip(results)
+            .flat_map(|((_, locale), results)| {
+                results
+                    .into_iter()
+                    .map(move |(key, result)| (locale.clone(), key, result))
+            })
+            .collect())
+    }
+
+    /// Sets value of key in one locale, adding the key when missing.
+    pub fn set(
+        &mut self,
+        locale: &str,
+        key: &KeyPath,
+        value: impl Into<Value>,
+    ) -> Result<Upserted, Error> {
+        let (parent, last) = split(key)?;
+        let index = self.index(locale)?;
+        let locale = &mut self.locales[index];
+
+        let mut json = locale.json.clone();
+        let result = upsert_value_at_key(&mut json, &parent, last, value)
+            .map_err(|e| Error::Conflicts(vec![format!("{} for file: {:?}", e, locale.file)]))?;
+        if result != Upserted::Unchanged {
+            locale.json = json;
+            self.changed.insert(index);
+        }
+        Ok(result)
+    }
+
+    /// Runs operation on a copy of locale, which replaces the locale only
+    /// when operation succeeded.
+    pub fn edit<T>(
+        &mut self,
+        locale: &str,
+        operation: impl FnOnce(&mut Value) -> Result<T, Vec<String>>,
+    ) -> Result<T, Error> {
+        let index = self.index(locale)?;
+        let locale = &mut self.locales[index];
+
+        let mut json = locale.json.clone();
+        let result = operation(&mut json).map_err(|messages| {
+            Error::Conflicts(
+                messages
+                    .into_iter()
+                    .map(|message| format!("{} for file: {:?}", message, locale.file))
+                    .collect(),
+            )
+        })?;
+        locale.json = json;
+        self.changed.insert(index);
+        Ok(result)
+    }
+
+    /// Removes key from every locale having it. Returns these locales.
+    pub fn remove(&mut self, key: &KeyPath) -> Result<Vec<String>, Error> {
+        let (parent, last) = split(key)?;
+        let values = self
+            .locales
+            .iter()
+            .filter(|locale| get_value_at_path(&locale.json, &key.segments()).is_some())
+            .map(|locale| (locale.name.clone(), Value::Null))
+            .collect::<Vec<_>>();
+        let removed = values.iter().map(|(name, _)| name.clone()).collect();
+
+        self.apply(values, |json, _| {
+            remove_key_at_path(json, &parent, last).map_err(|e| vec![e])
+        })?;
+        Ok(removed)
+    }
+
+    /// Renames key in every locale having it. Returns these locales.
+    pub fn rename(
+        &mut self,
+        from: &KeyPath,
+        to: &KeyPath,
+        strategy: ConflictStrategy,
+    ) -> Result<Vec<String>, Error> {
+        let values = self
+            .locales
+            .iter()
+            .filter(|locale| get_value_at_path(&locale.json, &from.segments()).is_some())
+            .map(|locale| (locale.name.clone(), Value::Null))
+            .collect::<Vec<_>>();
+        let renamed = values.iter().map(|(name, _)| name.clone()).collect();
+
+        self.apply(values, |json, _| {
+            match rename_key_at_path(json, from, to, strategy) {
+                Ok(()) | Err(RenameError::NotFound(_)) => Ok(()),
+                Err(error) => Err(vec![error.to_string()]),
+            }
+        })?;
+        Ok(renamed)
+    }
+
+    /// Compares every other locale with the reference one.
+    pub fn compare(&self, reference: &str) -> Result<Vec<JsonDiff>, Error> {
+        let reference = &self.locales[self.index(reference)?];
+        let reference_file = reference.file.to_string_lossy();
+
+        Ok(self
+            .locales
+            .iter()
+            .filter(|locale| locale.name != reference.name)
+            .map(|locale| {
+                get_missing_paths(
+                    &reference.json,
+                    &locale.json,
+                    &reference_file,
+                    &locale.file.to_string_lossy(),
+                )
+            })
+            .collect())
+    }
+
+    /// Moves values of other type than in the reference locale aside, see
+    /// [`fix_shape_conflicts`]. Returns locale, old and new path of every
+    /// moved value.
+    pub fn fix_shape(&mut self, reference: &str) -> Result<Vec<(String, KeyPath, KeyPath)>, Error> {
+        let reference = &self.locales[self.index(reference)?];
+        let values = self
+            .locales
+            .iter()
+            .filter(|locale| locale.name != reference.name)
+            .map(|locale| (locale.name.clone(), reference.json.clone()))
+            .collect::<Vec<_>>();
+        let locales = values
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        let mut moved = Vec::new();
+
+        self.apply(values, |json, reference| {
+            moved.push(fix_shape_conflicts(&reference, json).map_err(|e| vec![e])?);
+            Ok(())
+        })?;
+        Ok(locales
+            .into_iter()
+            .zip(moved)
+            .flat_map(|(locale, moved)| {
+                moved
+                    .into_iter()
+                    .map(move |(from, to)| (locale.clone(), from, to))
+            })
+            .collect())
+    }
+
+    /// Writes locales changed since the last save.
+    pub fn save(&mut self) -> Result<(), Error> {
+        for index in std::mem::take(&mut self.changed) {
+            let locale = &self.locales[index];
+            files::save_value_to_json_file(&locale.json, &locale.file).map_err(|source| {
+                Error::Io {
+                    file: locale.file.clone(),
+                    source,
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Writes every locale, also unchanged ones, e.g. to sort their keys.
+    pub fn save_all(&mut self) -> Result<(), Error> {
+        self.changed = (0..self.locales.len()).collect();
+        self.save()
+    }
+
+    fn index(&self, locale: &str) -> Result<usize, Error> {
+        self.locales
+            .iter()
+            .position(|candidate| candidate.name == locale)
+            .ok_or_else(|| Error::LocaleNotFound(locale.to_string()))
+    }
+
+    /// Runs operation on a copy of every given locale. Copies replace the
+    /// locales only when operation succeeded in all of them.
+    fn apply(
+        &mut self,
+        values: impl IntoIterator<Item = (String, Value)>,
+        mut operation: impl FnMut(&mut Value, Value) -> Result<(), Vec<String>>,
+    ) -> Result<(), Error> {
+        let mut values = values
+            .into_iter()
+            .map(|(locale, value)| Ok((self.index(&locale)?, value)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        values.sort_by_key(|(index, _)| *index);
+
+        let mut updated = Vec::new();
+        let mut conflicts = Vec::new();
+        for (index, value) in values {
+            let locale = &self.locales[index];
+            let mut json = locale.json.clone();
+            match operation(&mut json, value) {
+                Ok(()) => updated.push((index, json)),
+                Err(messages) => conflicts.extend(
+                    messages
+                        .into_iter()
+                        .map(|message| format!("{} for file: {:?}", message, locale.file)),
+                ),
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(Error::Conflicts(conflicts));
+        }
+        for (index, json) in updated {
+            self.locales[index].json = json;
+            self.changed.insert(index);
+        }
+        Ok(())
+    }
+}
+
+/// Index of the default reference locale given the number of keys in every
+/// locale: the one with the most keys, the first of equally complete ones.
+/// Translations usually lag behind the locale they are made from.
+pub fn default_reference(key_counts: impl IntoIterator<Item = usize>) -> Option<usize> {
+    key_counts
+        .into_iter()
+        .enumerate()
+        .min_by_key(|&(index, keys)| (std::cmp::Reverse(keys), index))
+        .map(|(index, _)| index)
+}
+
+fn split(key: &KeyPath) -> Result<(Vec<&str>, &str), Error> {
+    key.parent_and_key().map_err(Error::InvalidKey)
+}
+
+#[cfg(test)]
+mod reference {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_default_to_locale_with_most_keys() {
+        let catalog = Catalog::new([
+            (PathBuf::from("lang/de.json"), json!({ "a": "A" })),
+            (PathBuf::from("lang/en.json"), json!({ "a": "A", "b": "B" })),
+            (PathBuf::from("lang/sv.json"), json!({ "b": "B" })),
+        ]);
+
+        assert_eq!(catalog.reference(None).unwrap(), "en");
+    }
+
+    #[test]
+    fn should_take_requested_locale_or_file_name() {
+        let catalog = catalog();
+
+        assert_eq!(catalog.reference(Some("sv")).unwrap(), "sv");
+        assert_eq!(catalog.reference(Some("sv.json")).unwrap(), "sv");
+        assert!(matches!(
+            catalog.reference(Some("de")),
+            Err(Error::LocaleNotFound(locale)) if locale == "de"
+        ));
+    }
+
+    #[test]
+    fn should_fail_without_locales() {
+        let catalog = Catalog::new([]);
+
+        assert!(matches!(catalog.reference(None), Err(Error::NoLocales)));
+    }
+}
+
+#[cfg(test)]
+mod add {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_add_value_to_given_locales() {
+        let mut catalog = catalog();
+
+        catalog
+            .add(
+                &key("pax.children"),
+                [
+                    ("en".to_string(), json!("Children")),
+                    ("sv".to_string(), json!("Barn")),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            catalog.get("sv", &key("pax.children")).unwrap(),
+            Some(&json!("Barn"))
+        );
+        assert_eq!(catalog.changed, BTreeSet::from([0, 1]));
+    }
+
+    #[test]
+    fn should_change_no_locale_when_key_exists_in_one() {
+        let mut catalog = catalog();
+
+        let result = catalog.add(
+            &key("pax.adults"),
+            [
+                ("en".to_string(), json!("Adults")),
+                ("sv".to_string(), json!("Vuxna")),
+            ],
+        );
+
+        assert!(matches!(result, Err(Error::Conflicts(messages)) if messages.len() == 1));
+        assert_eq!(catalog.get("sv", &key("pax.adults")).unwrap(), None);
+        assert!(catalog.changed.is_empty());
+    }
+
+    #[test]
+    fn should_fail_for_unknown_locale() {
+        let result = catalog().add(&key("pax.children"), [("de".to_string(), json!("Kinder"))]);
+
+        assert!(matches!(result, Err(Error::LocaleNotFound(locale)) if locale == "de"));
+    }
+}
+
+#[cfg(test)]
+mod replace {
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_report_locales_missing_key() {
+        let mut catalog = catalog();
+
+        let result = catalog.replace(
+            &key("pax.adults"),
+            [
+                ("en".to_string(), json!("Grown-ups")),
+                ("sv".to_string(), json!("Vuxna")),
+            ],
+        );
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Key 'pax.adults' not found! for file: \"lang/sv.json\""
+        );
+        assert_eq!(
+            catalog.get("en", &key("pax.adults")).unwrap(),
+            Some(&json!("Adults"))
+        );
+    }
+}
+
+#[cfg(test)]
+mod set {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_mark_locale_changed_only_when_value_differs() {
+        let mut catalog = catalog();
+
+        let unchanged = catalog.set("sv", &key("pax.rooms"), "Rum").unwrap();
+        let created = catalog.set("sv", &key("pax.adults"), "Vuxna").unwrap();
+
+        assert_eq!(unchanged, Upserted::Unchanged);
+        assert_eq!(created, Upserted::Created);
+        assert_eq!(catalog.changed, BTreeSet::from([1]));
+    }
+
+    #[test]
+    fn should_not_change_locale_on_failure() {
+        let mut catalog = catalog();
+
+        let result = catalog.set("sv", &key("pax.rooms.count"), json!(2));
+
+        assert!(matches!(result, Err(Error::Conflicts(_))));
+        assert_eq!(
+            catalog.json("sv").unwrap(),
+            &json!({ "pax": { "rooms": "Rum" } })
+        );
+    }
+}
+
+#[cfg(test)]
+mod upsert {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_report_outcome_of_every_written_key() {
+        let mut catalog = catalog();
+
+        let result = catalog
+            .upsert(
+                &key("pax"),
+                [
+                    (
+                        "sv".to_string(),
+                        json!({ "rooms": "Rum", "adults": "Vuxna" }),
+                    ),
+                    ("en".to_string(), json!({ "rooms": "Rooms!" })),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                ("en".to_string(), key("pax.rooms"), Upserted::Updated),
+                ("sv".to_string(), key("pax.adults"), Upserted::Created),
+                ("sv".to_string(), key("pax.rooms"), Upserted::Unchanged),
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod edit {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_keep_locale_when_operation_fails() {
+        let mut catalog = catalog();
+
+        let result = catalog.edit("sv", |json| {
+            json["pax"]["rooms"] = json!("Rummen");
+            Err::<(), _>(vec!["Failed".to_string()])
+        });
+
+        assert!(
+            matches!(result, Err(Error::Conflicts(messages)) if messages == ["Failed for file: \"lang/sv.json\""])
+        );
+        assert_eq!(
+            catalog.get("sv", &key("pax.rooms")).unwrap(),
+            Some(&json!("Rum"))
+        );
+        assert_eq!(catalog.changed().count(), 0);
+    }
+}
+
+#[cfg(test)]
+mod remove {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn should_remove_key_from_locales_having_it() {
+        let mut catalog = catalog();
+
+        let removed = catalog.remove(&key("pax.adults")).unwrap();
+
+        assert_eq!(removed, vec!["en"]);
+        assert_eq!(catalog.paths(), BTreeSet::from([key("pax.rooms")]));
+    }
+}
+
+#[cfg(test)]
+mod rename {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_rename_key_in_locales_having_it() {
+        let mut catalog = catalog();
+
+        let renamed = catalog
+            .rename(
+                &key("pax.adults"),
+                &key("pax.grownUps"),
+                ConflictStrategy::Fail,
+            )
+            .unwrap();
+
+        assert_eq!(renamed, vec!["en"]);
+        assert_eq!(
+            catalog.get("en", &key("pax.grownUps")).unwrap(),
+            Some(&json!("Adults"))
+        );
+    }
+
+    #[test]
+    fn should_fail_when_destination_exists() {
+        let mut catalog = catalog();
+
+        let result = catalog.rename(
+            &key("pax.adults"),
+            &key("pax.rooms"),
+            ConflictStrategy::Fail,
+        );
+
+        assert!(matches!(result, Err(Error::Conflicts(_))));
+        assert!(catalog.changed.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod compare {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn should_compare_other_locales_with_reference() {
+        let result = catalog().compare("en").unwrap();
+
+        assert_eq!(
+            result,
+            vec![JsonDiff {
+                target_file: "lang/sv.json".to_string(),
+                reference_file: "lang/en.json".to_string(),
+                missing_in_target: vec![key("pax.adults")],
+                missing_in_reference: vec![],
+                shape_conflicts: vec![],
+            }]
+        );
+    }
+}
+
+#[cfg(test)]
+mod fix_shape {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_move_conflicting_values_aside_in_other_locales() {
+        let mut catalog = Catalog::new([
+            (
+                PathBuf::from("lang/en.json"),
+                json!({ "pax": { "rooms": "Rooms" } }),
+            ),
+            (PathBuf::from("lang/sv.json"), json!({ "pax": "Resenärer" })),
+        ]);
+
+        let result = catalog.fix_shape("en").unwrap();
+
+        assert_eq!(result, vec![("sv".to_string(), key("pax"), key("pax_old"))]);
+        assert_eq!(
+            catalog.json("sv").unwrap(),
+            &json!({ "pax": {}, "pax_old": "Resenärer" })
+        );
+    }
+}
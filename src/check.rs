@@ -0,0 +1,248 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::catalog::default_reference;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+
+// `{name}`, `{{ name }}`, `%{name}`, `%s`, `%1$d`.
+pub const PLACEHOLDER: &str = r"\{\{\s*[\w.]+\s*\}\}|%?\{\s*[\w.]+\s*\}|%(?:\d+\$)?[sdif@]";
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Problem {
+    pub file: PathBuf,
+    pub path: KeyPath,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}: {}", self.file, self.path, self.message)
+    }
+}
+
+/// Checks locale files of one directory against each other: keys missing in
+/// some locales, placeholders differing from the reference file, and
+/// suspicious values. Without `reference` the default one is used, see
+/// [`default_reference`]. Placeholders of keys the reference lacks are
+/// compared with the first file having them. When `only` is given, other
+/// keys are skipped.
+pub fn check_locales(
+    locales: &[(PathBuf, Value)],
+    reference: Option<&Path>,
+    only: Option<&BTreeSet<KeyPath>>,
+) -> Vec<Problem> {
+    let mut locales: Vec<&(PathBuf, Value)> = locales.iter().collect();
+    locales.sort_by(|a, b| a.0.cmp(&b.0));
+    let reference_file = reference.or_else(|| {
+        default_reference(locales.iter().map(|(_, json)| get_json_paths(json).len()))
+            .map(|i| locales[i].0.as_path())
+    });
+
+    let placeholder = Regex::new(PLACEHOLDER).unwrap();
+    let all_paths: BTreeSet<KeyPath> = locales
+        .iter()
+        .flat_map(|(_, json)| get_json_paths(json))
+        .filter(|path| only.is_none_or(|only| only.contains(path)))
+        .collect();
+
+    let mut problems = Vec::new();
+    for path in all_paths {
+        let values: Vec<(&PathBuf, Option<&Value>)> = locales
+            .iter()
+            .map(|(file, json)| (file, get_value_at_path(json, &path.segments())))
+            .collect();
+
+        let present: Vec<String> = values
+            .iter()
+            .filter(|(_, value)| value.is_some())
+            .map(|(file, _)| file_name(file))
+            .collect();
+        let reference = values
+            .iter()
+            .filter(|(file, _)| Some(file.as_path()) == reference_file)
+            .chain(&values)
+            .find_map(|(file, value)| match value {
+                Some(Value::String(text)) => Some((file, placeholders(&placeholder, text))),
+                _ => None,
+            });
+
+        for (file, value) in &values {
+            let mut report = |message: String| {
+                problems.push(Problem {
+                    file: file.to_path_buf(),
+                    path: path.clone(),
+                    message,
+                })
+            };
+
+            let Some(value) = value else {
+                report(format!("missing, present in {}", present.join(", ")));
+                continue;
+            };
+            let Value::String(text) = value else {
+                continue;
+            };
+
+            if let Some((reference_file, expected)) = &reference {
+                let found = placeholders(&placeholder, text);
+                if found != *expected {
+                    report(format!(
+                        "placeholders {} don't match {} ({})",
+                        format_placeholders(&found),
+                        file_name(reference_file),
+                        format_placeholders(expected)
+                    ));
+                }
+            }
+            for message in lint_value(text) {
+                report(message.to_string());
+            }
+        }
+    }
+
+    problems
+}
+
+/// Returns problems found in a single translated text.
+pub fn lint_value(text: &str) -> Vec<&'static str> {
+    let mut problems = Vec::new();
+
+    if text.trim().is_empty() {
+        problems.push("value is empty");
+    } else if text.trim() != text {
+        problems.push("value has leading or trailing whitespace");
+    }
+    if text.matches('{').count() != text.matches('}').count() {
+        problems.push("value has unbalanced braces");
+    }
+
+    problems
+}
+
+fn placeholders(placeholder: &Regex, text: &str) -> BTreeSet<String> {
+    placeholder
+        .find_iter(text)
+        .map(|found| found.as_str().replace(' ', ""))
+        .collect()
+}
+
+fn format_placeholders(placeholders: &BTreeSet<String>) -> String {
+    if placeholders.is_empty() {
+        return "(none)".to_string();
+    }
+    placeholders.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+fn file_name(file: &Path) -> String {
+    file.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod check_locales {
+    use super::*;
+    use crate::test_support::{locales, messages};
+    use serde_json::json;
+
+    #[test]
+    fn should_report_missing_keys() {
+        let result = check_locales(
+            &locales(json!({ "a": "A", "b": "B" }), json!({ "a": "A" })),
+            None,
+            None,
+        );
+
+        assert_eq!(
+            messages(result),
+            vec!["\"lang/sv.json\": b: missing, present in en.json"]
+        );
+    }
+
+    #[test]
+    fn should_report_placeholders_different_from_reference() {
+        let result = check_locales(
+            &locales(
+                json!({ "greeting": "Hi {name}, you have {count} rooms" }),
+                json!({ "greeting": "Hej {namn}, du har {{ count }} rum" }),
+            ),
+            None,
+            None,
+        );
+
+        assert_eq!(
+            messages(result),
+            vec![
+                "\"lang/sv.json\": greeting: placeholders {namn}, {{count}} don't match en.json ({count}, {name})"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_compare_placeholders_with_given_reference() {
+        let result = check_locales(
+            &locales(json!({ "a": "{x}" }), json!({ "a": "{y}" })),
+            Some(Path::new("lang/sv.json")),
+            None,
+        );
+
+        assert_eq!(
+            messages(result),
+            vec!["\"lang/en.json\": a: placeholders {x} don't match sv.json ({y})"]
+        );
+    }
+
+    #[test]
+    fn should_report_suspicious_values() {
+        let result = check_locales(
+            &locales(
+                json!({ "a": "A", "b": "B" }),
+                json!({ "a": " ", "b": "B " }),
+            ),
+            None,
+            None,
+        );
+
+        assert_eq!(
+            messages(result),
+            vec![
+                "\"lang/sv.json\": a: value is empty",
+                "\"lang/sv.json\": b: value has leading or trailing whitespace",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_check_only_given_keys() {
+        let only = BTreeSet::from([KeyPath::parse("a").unwrap()]);
+
+        let result = check_locales(
+            &locales(json!({ "a": "A", "b": "B" }), json!({ "a": "A" })),
+            None,
+            Some(&only),
+        );
+
+        assert_eq!(result, vec![]);
+    }
+}
+
+#[cfg(test)]
+mod lint_value {
+    use super::*;
+
+    #[test]
+    fn should_accept_regular_text() {
+        assert_eq!(lint_value("Hi {name}"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn should_report_unbalanced_braces() {
+        assert_eq!(lint_value("Hi {name"), vec!["value has unbalanced braces"]);
+    }
+}
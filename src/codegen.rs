@@ -0,0 +1,706 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::Catalog;
+use crate::check::PLACEHOLDER;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+
+/// Key written the way it's used in source code, with its interpolation
+/// parameters.
+struct Key {
+    dotted: String,
+    /// Parameter name and whether it's used as an object (`{user.name}`).
+    parameters: BTreeMap<String, bool>,
+}
+
+fn collect_keys(json: &Value) -> Vec<Key> {
+    let placeholder = Regex::new(PLACEHOLDER).unwrap();
+    let mut keys: Vec<Key> = get_json_paths(json)
+        .into_iter()
+        .map(|path| {
+            let text = get_value_at_path(json, &path.segments())
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            Key {
+                dotted: path.to_dotted(),
+                parameters: parameters(&placeholder, text),
+            }
+        })
+        .collect();
+    keys.sort_by(|a, b| a.dotted.cmp(&b.dotted));
+    keys
+}
+
+#[derive(Debug, PartialEq)]
+enum Part {
+    Text(String),
+    /// Name of a named placeholder, e.g. `user.name` for `{user.name}`.
+    Parameter(String),
+}
+
+/// Splits text into literal parts and named placeholders. Positional ones
+/// (`%s`) have no name and are kept as text.
+fn split_placeholders(placeholder: &Regex, text: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut end = 0;
+
+    for found in placeholder.find_iter(text) {
+        if !found.as_str().contains('{') {
+            continue;
+        }
+        literal.push_str(&text[end..found.start()]);
+        if !literal.is_empty() {
+            parts.push(Part::Text(std::mem::take(&mut literal)));
+        }
+        let name = found
+            .as_str()
+            .trim_matches(|c: char| c == '%' || c == '{' || c == '}' || c == ' ');
+        parts.push(Part::Parameter(name.to_string()));
+        end = found.end();
+    }
+
+    literal.push_str(&text[end..]);
+    if !literal.is_empty() {
+        parts.push(Part::Text(literal));
+    }
+    parts
+}
+
+/// Named placeholders of text, and whether each is used as an object
+/// (`{user.name}`).
+fn parameters(placeholder: &Regex, text: &str) -> BTreeMap<String, bool> {
+    let mut parameters = BTreeMap::new();
+    for part in split_placeholders(placeholder, text) {
+        let Part::Parameter(name) = part else {
+            continue;
+        };
+        let (root, nested) = match name.split_once('.') {
+            Some((root, _)) => (root, true),
+            None => (name.as_str(), false),
+        };
+        *parameters.entry(root.to_string()).or_default() |= nested;
+    }
+    parameters
+}
+
+/// Generates TypeScript declarations of every key in reference locale and
+/// parameters of its value. Output depends only on the locale, so it can be
+/// compared with the committed file.
+pub fn typescript(json: &Value) -> String {
+    let mut output = String::from(
+        "// Generated by `szambo codegen --lang=ts`, do not edit.\n\n\
+         export interface TranslationParams {\n",
+    );
+
+    for key in collect_keys(json) {
+        let parameters: Vec<String> = key
+            .parameters
+            .iter()
+            .map(|(name, nested)| {
+                let kind = if *nested {
+                    "Record<string, unknown>"
+                } else {
+                    "string | number"
+                };
+                format!("{}: {}", typescript_property(name), kind)
+            })
+            .collect();
+        let parameters = if parameters.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", parameters.join("; "))
+        };
+        output.push_str(&format!(
+            "  {}: {};\n",
+            serde_json::to_string(&key.dotted).unwrap(),
+            parameters
+        ));
+    }
+
+    output.push_str(
+        "}\n\n\
+         export type TranslationKey = keyof TranslationParams;\n\n\
+         /** Arguments of `t()`: parameters are required when the value has placeholders. */\n\
+         export type TranslationArgs<K extends TranslationKey> =\n  \
+         [keyof TranslationParams[K]] extends [never]\n    \
+         ? [key: K]\n    \
+         : [key: K, params: TranslationParams[K]];\n",
+    );
+    output
+}
+
+fn typescript_property(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|first| !first.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).unwrap()
+    }
+}
+
+/// Generated Rust module, one per key segment.
+#[derive(Default)]
+struct RustModule {
+    /// Identifier, original segment and content.
+    modules: BTreeMap<String, (String, RustModule)>,
+    /// Identifier and key of every accessor.
+    functions: BTreeMap<String, KeyPath>,
+}
+
+impl RustModule {
+    fn insert(&mut self, path: &KeyPath) -> Result<(), String> {
+        let segments = path.segments();
+        let Some((last, parents)) = segments.split_last() else {
+            return Ok(());
+        };
+
+        let mut module = self;
+        for segment in parents {
+            let identifier = rust_identifier(segment);
+            let (original, child) = module
+                .modules
+                .entry(identifier.clone())
+                .or_insert_with(|| (segment.to_string(), RustModule::default()));
+            if original != segment {
+                return Err(format!(
+                    "Segments '{}' and '{}' of '{}' both become module '{}'!",
+                    original, segment, path, identifier
+                ));
+            }
+            module = child;
+        }
+
+        let identifier = rust_identifier(last);
+        if let Some(other) = module.functions.insert(identifier.clone(), path.clone()) {
+            return Err(format!(
+                "Keys '{}' and '{}' both become function '{}'!",
+                other, path, identifier
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Generates Rust module with a `Locale` enum and an accessor for every text
+/// value, in modules nested like the keys. Values of every locale are
+/// embedded, and a value missing in some locale is a `compile_error!`, so it
+/// fails the build. Doc comments show the value in reference locale.
+pub fn rust(catalog: &Catalog, reference: &str) -> Result<String, String> {
+    catalog.json(reference).map_err(|e| e.to_string())?;
+
+    let mut locales: Vec<(String, &str)> = Vec::new();
+    for locale in catalog.locales() {
+        let variant = rust_type_name(locale);
+        if let Some((_, other)) = locales.iter().find(|(existing, _)| *existing == variant) {
+            return Err(format!(
+                "Locales '{}' and '{}' both become variant '{}'!",
+                other, locale, variant
+            ));
+        }
+        locales.push((variant, locale));
+    }
+
+    let mut root = RustModule::default();
+    for path in catalog.paths() {
+        let is_text = catalog
+            .locales()
+            .any(|locale| matches!(catalog.get(locale, &path), Ok(Some(Value::String(_)))));
+        if is_text {
+            root.insert(&path)?;
+        }
+    }
+
+    let mut output = String::from(
+        "// Generated by `szambo codegen --lang=rust`, do not edit.\n\n\
+         #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n\
+         pub enum Locale {\n",
+    );
+    for (variant, _) in &locales {
+        output.push_str(&format!("    {},\n", variant));
+    }
+    let variants: Vec<String> = locales
+        .iter()
+        .map(|(variant, _)| format!("Locale::{}", variant))
+        .collect();
+    output.push_str(&format!(
+        "}}\n\nimpl Locale {{\n    pub const ALL: [Locale; {}] = [{}];\n\n",
+        locales.len(),
+        variants.join(", ")
+    ));
+    output.push_str(
+        "    /// File name of the locale, without extension.\n    \
+         pub fn code(self) -> &'static str {\n        \
+         match self {\n",
+    );
+    for (variant, locale) in &locales {
+        output.push_str(&format!(
+            "            Locale::{} => {:?},\n",
+            variant, locale
+        ));
+    }
+    output.push_str(
+        "        }\n    }\n\n    \
+         pub fn from_code(code: &str) -> Option<Locale> {\n        \
+         Locale::ALL.into_iter().find(|locale| locale.code() == code)\n    \
+         }\n}\n",
+    );
+
+    let context = RustContext {
+        catalog,
+        reference,
+        locales: &locales,
+        placeholder: Regex::new(PLACEHOLDER).unwrap(),
+    };
+    context.write_module(&mut output, &root, "")?;
+    Ok(output)
+}
+
+struct RustContext<'a> {
+    catalog: &'a Catalog,
+    reference: &'a str,
+    /// Enum variant and name of every locale.
+    locales: &'a [(String, &'a str)],
+    placeholder: Regex,
+}
+
+impl RustContext<'_> {
+    fn write_module(
+        &self,
+        output: &mut String,
+        module: &RustModule,
+        indent: &str,
+    ) -> Result<(), String> {
+        let mut structs: BTreeMap<String, &KeyPath> = BTreeMap::new();
+        for (identifier, path) in &module.functions {
+            output.push('\n');
+            if let Some(params) = self.write_function(output, identifier, path, indent)?
+                && let Some(other) = structs.insert(params.clone(), path)
+            {
+                return Err(format!(
+                    "Keys '{}' and '{}' both become struct '{}'!",
+                    other, path, params
+                ));
+            }
+        }
+        for (identifier, (_, child)) in &module.modules {
+            output.push_str(&format!(
+                "\n{indent}pub mod {identifier} {{\n{indent}    use super::Locale;\n"
+            ));
+            self.write_module(output, child, &format!("{}    ", indent))?;
+            output.push_str(&format!("{}}}\n", indent));
+        }
+        Ok(())
+    }
+
+    /// Returns name of params struct, if the accessor needs one.
+    fn write_function(
+        &self,
+        output: &mut String,
+        identifier: &str,
+        path: &KeyPath,
+        indent: &str,
+    ) -> Result<Option<String>, String> {
+        let values: Vec<Option<Vec<Part>>> = self
+            .locales
+            .iter()
+            .map(|(_, locale)| match self.catalog.get(locale, path) {
+                Ok(Some(Value::String(text))) => Some(split_placeholders(&self.placeholder, text)),
+                _ => None,
+            })
+            .collect();
+
+        // Field identifier and placeholder name, of placeholders in any locale.
+        let mut fields: BTreeMap<String, String> = BTreeMap::new();
+        for part in values.iter().flatten().flatten() {
+            if let Part::Parameter(name) = part {
+                let field = rust_identifier(&name.replace('.', "_"));
+                if let Some(other) = fields.insert(field.clone(), name.clone())
+                    && other != *name
+                {
+                    return Err(format!(
+                        "Placeholders '{}' and '{}' of '{}' both become field '{}'!",
+                        other, name, path, field
+                    ));
+                }
+            }
+        }
+
+        if let Ok(Some(Value::String(text))) = self.catalog.get(self.reference, path) {
+            for line in text.lines() {
+                let line = format!("{}/// {}", indent, line);
+                output.push_str(line.trim_end());
+                output.push('\n');
+            }
+        }
+
+        let params = format!("{}Params", rust_type_name(identifier));
+        if fields.is_empty() {
+            output.push_str(&format!(
+                "{indent}pub fn {identifier}(locale: Locale) -> &'static str {{\n"
+            ));
+        } else {
+            output.push_str(&format!(
+                "{indent}pub fn {identifier}(locale: Locale, params: &{params}<'_>) -> String {{\n"
+            ));
+        }
+        output.push_str(&format!("{indent}    match locale {{\n"));
+
+        for ((variant, locale), parts) in self.locales.iter().zip(&values) {
+            let value = match parts {
+                None => format!(
+                    "compile_error!({:?})",
+                    format!("Key '{}' is missing in {}", path, locale)
+                ),
+                Some(parts) if fields.is_empty() => format!("{:?}", join_text(parts)),
+                Some(parts) => rust_format(parts, &fields),
+            };
+            output.push_str(&format!("{indent}        Locale::{variant} => {value},\n"));
+        }
+        output.push_str(&format!("{indent}    }}\n{indent}}}\n"));
+
+        if fields.is_empty() {
+            return Ok(None);
+        }
+        output.push_str(&format!("\n{indent}pub struct {params}<'a> {{\n"));
+        for field in fields.keys() {
+            output.push_str(&format!(
+                "{indent}    pub {field}: &'a dyn std::fmt::Display,\n"
+            ));
+        }
+        output.push_str(&format!("{indent}}}\n"));
+        Ok(Some(params))
+    }
+}
+
+fn join_text(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .map(|part| match part {
+            Part::Text(text) | Part::Parameter(text) => text.as_str(),
+        })
+        .collect()
+}
+
+/// Builds `format!` call filling placeholders from params struct.
+fn rust_format(parts: &[Part], fields: &BTreeMap<String, String>) -> String {
+    let mut template = String::new();
+    let mut arguments = Vec::new();
+    for part in parts {
+        match part {
+            Part::Text(text) => template.push_str(&text.replace('{', "{{").replace('}', "}}")),
+            Part::Parameter(name) => {
+                let field = fields
+                    .iter()
+                    .find(|(_, placeholder)| *placeholder == name)
+                    .map(|(field, _)| field.as_str())
+                    .unwrap_or_default();
+                template.push_str("{}");
+                arguments.push(format!("params.{}", field));
+            }
+        }
+    }
+
+    if arguments.is_empty() {
+        return format!("{:?}.to_string()", join_text(parts));
+    }
+    format!("format!({:?}, {})", template, arguments.join(", "))
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Turns key segment into a snake_case identifier, e.g. `tooLarge` into
+/// `too_large`.
+fn rust_identifier(segment: &str) -> String {
+    let mut identifier = String::new();
+    let mut after_lowercase = false;
+    for c in segment.chars() {
+        if c.is_uppercase() {
+            if after_lowercase {
+                identifier.push('_');
+            }
+            identifier.extend(c.to_lowercase());
+            after_lowercase = false;
+        } else if c.is_alphanumeric() || c == '_' {
+            identifier.push(c);
+            after_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        } else {
+            identifier.push('_');
+            after_lowercase = false;
+        }
+    }
+
+    if identifier.is_empty()
+        || identifier == "_"
+        || identifier.starts_with(|c: char| c.is_ascii_digit())
+    {
+        identifier.insert(0, '_');
+    }
+    match identifier.as_str() {
+        // These can't be raw identifiers.
+        "self" | "super" | "crate" => identifier + "_",
+        keyword if RUST_KEYWORDS.contains(&keyword) => format!("r#{}", identifier),
+        _ => identifier,
+    }
+}
+
+/// Turns locale or key segment into a PascalCase type name, e.g. `pt-BR`
+/// into `PtBr`.
+fn rust_type_name(segment: &str) -> String {
+    let name: String = rust_identifier(segment)
+        .trim_start_matches("r#")
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod parameters {
+    use super::*;
+
+    #[test]
+    fn should_collect_named_placeholders() {
+        let placeholder = Regex::new(PLACEHOLDER).unwrap();
+
+        let result = parameters(
+            &placeholder,
+            "Hi {name}, {{ count }} rooms for %{user.name} (%s)",
+        );
+
+        assert_eq!(
+            result,
+            BTreeMap::from([
+                ("count".to_string(), false),
+                ("name".to_string(), false),
+                ("user".to_string(), true),
+            ])
+        );
+    }
+}
+
+#[cfg(test)]
+mod typescript {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_declare_keys_with_parameters() {
+        let result = typescript(&json!({
+            "pax": { "rooms": "Rooms", "greeting": "Hi {name}, room {0}" },
+            "items": ["a", "b"],
+        }));
+
+        assert!(result.contains(
+            "export interface TranslationParams {\n  \
+             \"items\": {};\n  \
+             \"pax.greeting\": { \"0\": string | number; name: string | number };\n  \
+             \"pax.rooms\": {};\n\
+             }\n"
+        ));
+    }
+}
+
+#[cfg(test)]
+mod split_placeholders {
+    use super::*;
+
+    #[test]
+    fn should_keep_positional_placeholders_as_text() {
+        let placeholder = Regex::new(PLACEHOLDER).unwrap();
+
+        let result = split_placeholders(&placeholder, "Hi {name}, %s left");
+
+        assert_eq!(
+            result,
+            vec![
+                Part::Text("Hi ".to_string()),
+                Part::Parameter("name".to_string()),
+                Part::Text(", %s left".to_string()),
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod rust {
+    use super::*;
+    use crate::test_support::{locales, temp_dir};
+    use serde_json::json;
+    use std::fs;
+    use std::process::Command;
+
+    #[test]
+    fn should_generate_accessor_with_params() {
+        let result = rust(
+            &Catalog::new(locales(
+                json!({ "pax": { "greeting": "Hi {name} {{x}}" } }),
+                json!({ "pax": { "greeting": "Hej {name}" } }),
+            )),
+            "en",
+        )
+        .unwrap();
+
+        assert!(result.contains(
+            "pub mod pax {\n    \
+             use super::Locale;\n\n    \
+             /// Hi {name} {{x}}\n    \
+             pub fn greeting(locale: Locale, params: &GreetingParams<'_>) -> String {\n        \
+             match locale {\n            \
+             Locale::En => format!(\"Hi {} {}\", params.name, params.x),\n            \
+             Locale::Sv => format!(\"Hej {}\", params.name),\n"
+        ));
+        assert!(result.contains("    pub struct GreetingParams<'a> {\n"));
+    }
+
+    #[test]
+    fn should_fail_build_for_key_missing_in_locale() {
+        let result = rust(
+            &Catalog::new(locales(json!({ "a": "A", "b": "B" }), json!({ "a": "A" }))),
+            "en",
+        )
+        .unwrap();
+
+        assert!(result.contains("Locale::Sv => compile_error!(\"Key 'b' is missing in sv\"),"));
+    }
+
+    #[test]
+    fn should_report_keys_becoming_same_function() {
+        let result = rust(
+            &Catalog::new(locales(json!({ "fooBar": "A", "foo_bar": "B" }), json!({}))),
+            "en",
+        );
+
+        assert_eq!(
+            result,
+            Err("Keys 'fooBar' and 'foo_bar' both become function 'foo_bar'!".to_string())
+        );
+    }
+
+    #[test]
+    fn should_report_keys_becoming_same_params_struct() {
+        let result = rust(
+            &Catalog::new(locales(
+                json!({ "foo": "Hi {name}", "foo_": "Bye {name}" }),
+                json!({}),
+            )),
+            "en",
+        );
+
+        assert_eq!(
+            result,
+            Err("Keys 'foo' and 'foo_' both become struct 'FooParams'!".to_string())
+        );
+    }
+
+    /// Builds generated module into a program running `main`, returns its
+    /// output or compiler errors.
+    fn compile_and_run(name: &str, generated: &str, main: &str) -> Result<String, String> {
+        let dir = temp_dir(name);
+        let source = dir.join("main.rs");
+        let binary = dir.join("main");
+        fs::write(
+            &source,
+            format!("{}\nfn main() {{\n{}\n}}\n", generated, main),
+        )
+        .unwrap();
+
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let build = Command::new(rustc)
+            .args(["--edition=2024", "-o"])
+            .args([&binary, &source])
+            .output()
+            .unwrap();
+        if !build.status.success() {
+            return Err(String::from_utf8_lossy(&build.stderr).to_string());
+        }
+        let run = Command::new(&binary).output().unwrap();
+        Ok(String::from_utf8_lossy(&run.stdout).to_string())
+    }
+
+    #[test]
+    fn should_generate_module_that_compiles() {
+        let generated = rust(
+            &Catalog::new(locales(
+                json!({ "pax": { "rooms": "Rooms", "greeting": "Hi {name}" }, "type": "Type" }),
+                json!({ "pax": { "rooms": "Rum", "greeting": "Hej {name}" }, "type": "Typ" }),
+            )),
+            "en",
+        )
+        .unwrap();
+
+        let result = compile_and_run(
+            "compiles",
+            &generated,
+            r#"let sv = Locale::from_code("sv").unwrap();
+            let params = pax::GreetingParams { name: &"Ada" };
+            println!("{} {} {}", pax::rooms(sv), pax::greeting(sv, &params), r#type(Locale::En));"#,
+        );
+
+        assert_eq!(result, Ok("Rum Hej Ada Type\n".to_string()));
+    }
+
+    #[test]
+    fn should_generate_module_failing_build_for_missing_key() {
+        let generated = rust(
+            &Catalog::new(locales(json!({ "a": "A", "b": "B" }), json!({ "a": "A" }))),
+            "en",
+        )
+        .unwrap();
+
+        let result = compile_and_run("missing", &generated, "");
+
+        assert!(result.unwrap_err().contains("Key 'b' is missing in sv"));
+    }
+}
+
+#[cfg(test)]
+mod rust_identifier {
+    use super::*;
+
+    #[test]
+    fn should_convert_to_snake_case() {
+        assert_eq!(rust_identifier("tooLarge"), "too_large");
+        assert_eq!(rust_identifier("file-name"), "file_name");
+        assert_eq!(rust_identifier("404"), "_404");
+        assert_eq!(rust_identifier("type"), "r#type");
+        assert_eq!(rust_identifier("self"), "self_");
+    }
+}
+
+#[cfg(test)]
+mod rust_type_name {
+    use super::*;
+
+    #[test]
+    fn should_convert_to_pascal_case() {
+        assert_eq!(rust_type_name("pt-BR"), "PtBr");
+        assert_eq!(rust_type_name("type"), "Type");
+    }
+}
@@ -1,19 +1,54 @@
-use crate::files;
-use crate::json::compare::get_missing_paths;
-use crate::json::insert::insert_under_key;
-use crate::json::paths::get_json_paths;
-use crate::json::remove::remove_key_at_path;
-use crate::json::rename::rename_key_at_path;
-use crate::json::replace::replace_value_at_key;
-use crate::search::find_unused_paths;
-use crate::utils;
-
-pub fn add_to_many_command(key: String, from: String, where_: String, files: Option<String>) {
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::parser::{Api, Lang, ProviderArgs};
+
+use szambo::batch::{self, Outcome};
+use szambo::blame::blame_key;
+use szambo::check::{Problem, check_locales};
+use szambo::codegen;
+use szambo::files;
+use szambo::git;
+use szambo::glossary::{check_glossary, load_glossary};
+use szambo::json::compare::get_missing_paths;
+use szambo::json::diff::diff_values;
+use szambo::json::insert::insert_under_key;
+use szambo::json::key_path::KeyPath;
+use szambo::json::lookup::{ensure_path_is_free, get_value_at_path};
+use szambo::json::merge::{Conflict, three_way_merge};
+use szambo::json::paths::get_json_paths;
+use szambo::json::rename::{ConflictStrategy, insert_value_at_path, take_value_at_path};
+use szambo::json::schema::{generate_schema, validate_against_schema};
+use szambo::json::selector::Selector;
+use szambo::json::upsert::upsert_value_at_key;
+use szambo::lsp;
+use szambo::memory::{Memory, SUGGESTION_SCORE};
+use szambo::pseudo::pseudo_localize;
+use szambo::search::{find_unused_paths, mentions_key};
+use szambo::serve;
+use szambo::translate::{CommandProvider, HttpApi, HttpProvider, Provider, translate_texts};
+use szambo::tui;
+use szambo::utils;
+use szambo::watch;
+use szambo::{Catalog, Error};
+
+pub fn add_to_many_command(
+    key: String,
+    from: String,
+    where_: String,
+    files: Option<String>,
+    upsert: bool,
+) {
     let updates = files::load_json_into_hash_map(&from).unwrap();
-    let files_in_dir = files::list_files_in_dir(&where_).unwrap();
-    let (path, new_key) = utils::get_path_and_key(&key).unwrap();
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let files_in_dir = locale_files(&catalog);
+    let key_path = KeyPath::parse(&key).unwrap();
 
-    if let Some(required_keys) = files {
+    let selected_files: Vec<&PathBuf> = if let Some(required_keys) = files {
         let required_keys = utils::parse_limit(&required_keys).unwrap();
 
         println!(
@@ -26,16 +61,10 @@ pub fn add_to_many_command(key: String, from: String, where_: String, files: Opt
         files_in_dir
             .iter()
             .filter(|file| {
-                let stem = utils::get_file_stem(&file).unwrap();
+                let stem = utils::get_file_stem(file).unwrap();
                 required_keys.contains(&stem)
             })
-            .for_each(|file| {
-                let mut json = files::load_json_into_value(&file).unwrap();
-                let hash_map_key = utils::get_file_stem(&file).unwrap();
-                let value = updates.get(&hash_map_key).unwrap();
-                insert_under_key(&mut json, &path, &new_key, value).unwrap();
-                files::save_value_to_json_file(&json, file).unwrap();
-            });
+            .collect()
     } else {
         println!(
             "Adding from: '{}' to: '{}' under key: {}",
@@ -43,50 +72,108 @@ pub fn add_to_many_command(key: String, from: String, where_: String, files: Opt
         );
         utils::validate_paths_and_updates_file_keys_match(&updates, &files_in_dir).unwrap();
 
-        files.iter().for_each(|file| {
-            let mut json = files::load_json_into_value(&file).unwrap();
-            let hash_map_key = utils::get_file_stem(&file).unwrap();
-            let value = updates.get(&hash_map_key).unwrap();
-            insert_under_key(&mut json, &path, &new_key, value).unwrap();
-            files::save_value_to_json_file(&json, file).unwrap();
-        });
+        files_in_dir.iter().collect()
+    };
+
+    let values = selected_values(&selected_files, &updates);
+    if upsert {
+        upsert_into_locales(&mut catalog, &key_path, values);
+    } else {
+        exit_on_conflicts(catalog.add(&key_path, values));
+        catalog.save().unwrap();
+    }
+
+    if selected_files.len() < files_in_dir.len() {
+        // Suggest from the default reference if it was written to, or else
+        // from any of the written locales.
+        let written: Vec<String> = selected_files
+            .iter()
+            .map(|file| utils::get_file_stem(file).unwrap())
+            .collect();
+        let default = catalog.reference(None).unwrap();
+        let Some(reference) = written
+            .iter()
+            .find(|locale| *locale == default)
+            .or(written.first())
+        else {
+            return;
+        };
+        let added: Vec<KeyPath> = get_json_paths(catalog.json(reference).unwrap())
+            .into_iter()
+            .filter(|path| path.starts_with(&key_path))
+            .collect();
+        for locale in catalog.locales().filter(|locale| locale != reference) {
+            let missing = added
+                .iter()
+                .filter(|path| catalog.get(locale, path).unwrap().is_none());
+            print_suggestions(&catalog, reference, locale, missing);
+        }
     }
 }
 
-pub fn add_to_single_command(from: String, where_: String) {
+pub fn add_to_single_command(from: String, where_: String, upsert: bool) {
     println!("Adding from {} into {}", from, where_);
 
     let updates = files::load_json_into_hash_map(&from).unwrap();
     let mut json = files::load_json_into_value(&where_).unwrap();
+    let mut report = Vec::new();
 
     updates.iter().for_each(|(full_path, value)| {
-        let (path, new_key) = utils::get_path_and_key(&full_path).unwrap();
-        insert_under_key(&mut json, &path, &new_key, value).unwrap();
+        let key_path = KeyPath::parse(full_path).unwrap();
+        let (path, new_key) = key_path.parent_and_key().unwrap();
+        if upsert {
+            let result = upsert_value_at_key(&mut json, &path, new_key, value.clone()).unwrap();
+            report.push(format!("{} {}", key_path, result));
+        } else {
+            insert_under_key(&mut json, &path, new_key, value.clone()).unwrap();
+        }
     });
     files::save_value_to_json_file(&json, &where_).unwrap();
+
+    report.sort();
+    for line in report {
+        println!("{}", line);
+    }
 }
 
 pub fn remove_command(key: String, where_: String) {
     println!("Removing key '{}' from '{}'", key, where_);
-    let files = files::list_files_in_dir(&where_).unwrap();
-    let (path, key_to_remove) = utils::get_path_and_key(&key).unwrap();
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let selector = Selector::parse(&key).unwrap();
+    let matched = selector.select(&catalog.node_paths());
 
-    files.iter().for_each(|file| {
-        let mut json = files::load_json_into_value(&file).unwrap();
-        let result = remove_key_at_path(&mut json, &path, key_to_remove);
-        if let Err(message) = result {
-            println!("{} for file: {:?}", message, file);
-        }
-        files::save_value_to_json_file(&json, file).unwrap();
-    });
+    if matched.is_empty() {
+        println!("No keys match '{}'", key);
+        return;
+    }
+
+    println!("Matched keys:\n");
+    for key_path in &matched {
+        println!("{}", key_path);
+    }
+    println!();
+
+    let mut matched = matched;
+    matched.sort_by(KeyPath::removal_order);
+    for key_path in &matched {
+        exit_on_conflicts(catalog.remove(key_path));
+    }
+    catalog.save().unwrap();
 }
 
-pub fn replace_command(key: String, from: String, where_: String, files: Option<String>) {
+pub fn replace_command(
+    key: String,
+    from: String,
+    where_: String,
+    files: Option<String>,
+    upsert: bool,
+) {
     let updates = files::load_json_into_hash_map(&from).unwrap();
-    let files_in_dir = files::list_files_in_dir(&where_).unwrap();
-    let (path, key_to_replace) = utils::get_path_and_key(&key).unwrap();
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let files_in_dir = locale_files(&catalog);
+    let key_path = KeyPath::parse(&key).unwrap();
 
-    if let Some(required_keys) = files {
+    let selected_files: Vec<&PathBuf> = if let Some(required_keys) = files {
         let required_keys = utils::parse_limit(&required_keys).unwrap();
         println!(
             "Replacing key '{}' with data from '{}' in '{} only for {:?}'",
@@ -97,17 +184,10 @@ pub fn replace_command(key: String, from: String, where_: String, files: Option<
         files_in_dir
             .iter()
             .filter(|file| {
-                let stem = utils::get_file_stem(&file).unwrap();
+                let stem = utils::get_file_stem(file).unwrap();
                 required_keys.contains(&stem)
             })
-            .for_each(|file| {
-                let hash_map_key = utils::get_file_stem(&file).unwrap();
-                let new_value = updates.get(&hash_map_key).unwrap();
-
-                let mut json = files::load_json_into_value(&file).unwrap();
-                replace_value_at_key(&mut json, &path, key_to_replace, new_value).unwrap();
-                files::save_value_to_json_file(&json, file).unwrap();
-            });
+            .collect()
     } else {
         println!(
             "Replacing key '{}' with data from '{}' in '{}'",
@@ -115,39 +195,796 @@ pub fn replace_command(key: String, from: String, where_: String, files: Option<
         );
         utils::validate_paths_and_updates_file_keys_match(&updates, &files_in_dir).unwrap();
 
-        files_in_dir.iter().for_each(|file| {
-            let hash_map_key = utils::get_file_stem(&file).unwrap();
-            let new_value = updates.get(&hash_map_key).unwrap();
+        files_in_dir.iter().collect()
+    };
 
-            let mut json = files::load_json_into_value(&file).unwrap();
-            replace_value_at_key(&mut json, &path, key_to_replace, new_value).unwrap();
-            files::save_value_to_json_file(&json, file).unwrap();
-        });
+    let values = selected_values(&selected_files, &updates);
+    if upsert {
+        return upsert_into_locales(&mut catalog, &key_path, values);
     }
+
+    exit_on_conflicts(catalog.replace(&key_path, values));
+    catalog.save().unwrap();
 }
 
-pub fn rename_command(from: String, to: String, where_: String) {
+pub fn rename_command(from: String, to: String, where_: String, strategy: ConflictStrategy) {
     println!("Renaming '{}' to '{}' in {}", from, to, where_);
 
-    let from_path: Vec<&str> = from.split('.').collect();
-    let to_path: Vec<&str> = to.split('.').collect();
-    let files = files::list_files_in_dir(&where_).unwrap();
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let moves = match map_selected_keys(&from, &to, &catalog.node_paths()) {
+        Ok(moves) => moves,
+        Err(error) => return exit_on_errors(&[error]),
+    };
+
+    if moves.is_empty() {
+        println!("No keys match '{}'", from);
+        return;
+    }
+
+    let mut moves = moves;
+    moves.sort_by(|a, b| a.0.removal_order(&b.0));
+    let mut conflicts = Vec::new();
+    for (from_path, to_path) in &moves {
+        match catalog.rename(from_path, to_path, strategy) {
+            Ok(renamed) => {
+                let locales: Vec<String> = catalog.locales().map(str::to_string).collect();
+                for locale in locales.iter().filter(|locale| !renamed.contains(locale)) {
+                    println!(
+                        "Key '{}' not found during rename! for file: {:?}",
+                        from_path,
+                        catalog.file(locale).unwrap()
+                    );
+                }
+            }
+            Err(Error::Conflicts(messages)) => conflicts.extend(messages),
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    exit_on_errors(&conflicts);
+    catalog.save().unwrap();
+}
+
+pub fn copy_command(from: String, to: String, where_: String, to_where: Option<String>) {
+    println!("Copying '{}' to '{}' in {}", from, to, where_);
+    transfer_keys(&from, &to, &where_, to_where.as_deref(), false);
+}
+
+pub fn move_command(from: String, to: String, where_: String, to_where: Option<String>) {
+    println!("Moving '{}' to '{}' in {}", from, to, where_);
+    transfer_keys(&from, &to, &where_, to_where.as_deref(), true);
+}
+
+/// Copies or moves selected keys in every file in `where_`. When `to_where` is
+/// given, keys land in the file with the same name in that directory. Nothing
+/// is written if any destination key is already taken.
+fn transfer_keys(from: &str, to: &str, where_: &str, to_where: Option<&str>, remove_source: bool) {
+    // The same directory given twice would be saved twice, losing the changes
+    // of the first save.
+    let to_where = to_where.filter(|dir| {
+        let canonical = |dir: &str| fs::canonicalize(dir).ok();
+        canonical(dir).is_none() || canonical(dir) != canonical(where_)
+    });
+    let mut source = Catalog::open(where_).unwrap();
+    let pairs = match map_selected_keys(from, to, &source.node_paths()) {
+        Ok(pairs) => pairs,
+        Err(error) => return exit_on_errors(&[error]),
+    };
+
+    if pairs.is_empty() {
+        println!("No keys match '{}'", from);
+        return;
+    }
 
-    files.iter().for_each(|file| {
-        let mut json = files::load_json_into_value(&file).unwrap();
-        rename_key_at_path(&mut json, &from_path, &to_path).unwrap();
-        files::save_value_to_json_file(&json, file).unwrap();
+    // Destination has a file for every source locale, empty if it is new.
+    let mut destination = to_where.map(|dir| {
+        let existing = match Path::new(dir).exists() {
+            true => Catalog::open(dir).unwrap(),
+            false => Catalog::new([]),
+        };
+        Catalog::new(source.locales().map(|locale| {
+            let name = source.file(locale).unwrap().file_name().unwrap();
+            let json = existing
+                .json(locale)
+                .cloned()
+                .unwrap_or_else(|_| Value::Object(Default::default()));
+            (Path::new(dir).join(name), json)
+        }))
     });
+    let locales: Vec<String> = source.locales().map(str::to_string).collect();
+
+    let mut conflicts = Vec::new();
+    for locale in &locales {
+        let target = destination.as_ref().unwrap_or(&source);
+        for (from_path, to_path) in &pairs {
+            if source.get(locale, from_path).unwrap().is_none() {
+                continue;
+            }
+            if let Err(message) = ensure_path_is_free(target.json(locale).unwrap(), to_path) {
+                conflicts.push(format!(
+                    "{} for file: {:?}",
+                    message,
+                    target.file(locale).unwrap()
+                ));
+            }
+        }
+    }
+
+    exit_on_errors(&conflicts);
+
+    // Every value is read before any is inserted, later array elements first,
+    // so taking one doesn't shift indexes of those still to go.
+    let mut removal_order: Vec<usize> = (0..pairs.len()).collect();
+    removal_order.sort_by(|&a, &b| pairs[a].0.removal_order(&pairs[b].0));
+    let read_values = |json: &mut Value, take: bool| {
+        let mut values: Vec<Option<Value>> = vec![None; pairs.len()];
+        for &i in &removal_order {
+            let segments = pairs[i].0.segments();
+            values[i] = match take {
+                true => take_value_at_path(json, &segments),
+                false => get_value_at_path(json, &segments).cloned(),
+            };
+        }
+        values
+    };
+    let insert_values = |json: &mut Value, values: Vec<Option<Value>>| {
+        let errors: Vec<String> = pairs
+            .iter()
+            .zip(values)
+            .filter_map(|((_, to_path), value)| {
+                insert_value_at_path(json, &to_path.segments(), value?)
+                    .err()
+                    .map(|error| error.to_string())
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    };
+
+    for locale in &locales {
+        let file = source.file(locale).unwrap().to_path_buf();
+        let result = match &mut destination {
+            Some(destination) => {
+                let values: Vec<Option<Value>> = pairs
+                    .iter()
+                    .map(|(from_path, _)| source.get(locale, from_path).unwrap().cloned())
+                    .collect();
+                let found: Vec<bool> = values.iter().map(Option::is_some).collect();
+                destination
+                    .edit(locale, |json| insert_values(json, values))
+                    .and_then(|()| match remove_source {
+                        true => source.edit(locale, |json| {
+                            read_values(json, true);
+                            Ok(())
+                        }),
+                        false => Ok(()),
+                    })
+                    .map(|()| found)
+            }
+            None => source.edit(locale, |json| {
+                let values = read_values(json, remove_source);
+                let found = values.iter().map(Option::is_some).collect();
+                insert_values(json, values).map(|()| found)
+            }),
+        };
+
+        match result {
+            Ok(found) => {
+                for ((from_path, _), found) in pairs.iter().zip(found) {
+                    if !found {
+                        println!("Key '{}' not found for file: {:?}", from_path, file);
+                    }
+                }
+            }
+            Err(Error::Conflicts(messages)) => {
+                println!("File {:?} left unchanged:", file);
+                for message in messages {
+                    println!("{}", message);
+                }
+            }
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    if let Some(destination) = &mut destination {
+        fs::create_dir_all(to_where.unwrap()).unwrap();
+        destination.save().unwrap();
+    }
+    source.save().unwrap();
+}
+
+pub fn apply_command(from: String, where_: String) {
+    println!("Applying batch from '{}' to '{}'", from, where_);
+
+    let entries = batch::load_batch(&from).unwrap();
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let locales: Vec<String> = catalog.locales().map(str::to_string).collect();
+
+    let mut errors = Vec::new();
+    for entry in &entries {
+        for locale in entry.values.keys() {
+            if !locales.contains(locale) {
+                errors.push(format!(
+                    "Unknown locale '{}' for key '{}'",
+                    locale, entry.path
+                ));
+            }
+        }
+    }
+
+    let mut summary: Vec<(&String, BTreeMap<Outcome, usize>)> = Vec::new();
+    for locale in &locales {
+        let result = catalog.edit(locale, |json| {
+            let mut counts = BTreeMap::new();
+            let mut errors = Vec::new();
+            for entry in &entries {
+                match batch::apply_entry(json, locale, entry) {
+                    Ok(Some(outcome)) => *counts.entry(outcome).or_insert(0) += 1,
+                    Ok(None) => {}
+                    Err(message) => {
+                        errors.push(format!("{} ({:?} '{}')", message, entry.op, entry.path))
+                    }
+                }
+            }
+            if errors.is_empty() {
+                Ok(counts)
+            } else {
+                Err(errors)
+            }
+        });
+        match result {
+            Ok(counts) => summary.push((locale, counts)),
+            Err(Error::Conflicts(messages)) => errors.extend(messages),
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    exit_on_errors(&errors);
+    catalog.save().unwrap();
+
+    println!("Applied {} entries:\n", entries.len());
+    for (locale, counts) in summary {
+        let counts = counts
+            .iter()
+            .map(|(outcome, count)| format!("{} {}", outcome, count))
+            .collect::<Vec<_>>();
+        let counts = if counts.is_empty() {
+            "no changes".to_string()
+        } else {
+            counts.join(", ")
+        };
+        println!("{:?}: {}", catalog.file(locale).unwrap(), counts);
+    }
+}
+
+pub fn merge_command(
+    base: String,
+    ours: String,
+    theirs: String,
+    output: Option<String>,
+    conflicts_file: Option<String>,
+) {
+    let output = PathBuf::from(output.unwrap_or_else(|| ours.clone()));
+    println!(
+        "Merging base: {} ours: {} theirs: {} into: {:?}",
+        base, ours, theirs, output
+    );
+
+    // (label, base, ours, theirs, output) for every merged file.
+    let mut loaded = Vec::new();
+    let mut errors = Vec::new();
+    if Path::new(&ours).is_dir() {
+        let mut catalogs = Vec::new();
+        for dir in [&base, &ours, &theirs] {
+            match Path::new(dir).is_dir() {
+                true => match Catalog::open(dir) {
+                    Ok(catalog) => catalogs.push(catalog),
+                    Err(error) => errors.push(error.to_string()),
+                },
+                false => catalogs.push(Catalog::new([])),
+            }
+        }
+        exit_on_errors(&errors);
+
+        let locales: BTreeSet<String> = catalogs
+            .iter()
+            .flat_map(|catalog| catalog.locales().map(str::to_string))
+            .collect();
+        fs::create_dir_all(&output).unwrap();
+
+        for locale in locales {
+            let [base, ours, theirs] = [0, 1, 2].map(|i| catalogs[i].json(&locale).ok().cloned());
+            let name = format!("{}.json", locale);
+            loaded.push((name.clone(), base, ours, theirs, output.join(name)));
+        }
+    } else {
+        let label = ours.clone();
+        match [&base, &ours, &theirs].map(|path| load_optional_json(Path::new(path))) {
+            [Ok(base), Ok(ours), Ok(theirs)] => loaded.push((label, base, ours, theirs, output)),
+            results => errors.extend(results.into_iter().filter_map(Result::err)),
+        }
+    }
+    exit_on_errors(&errors);
+
+    let mut all_conflicts: Vec<(String, Conflict)> = Vec::new();
+    for (label, base, ours, theirs, output) in loaded {
+        let (merged, conflicts) = three_way_merge(base.as_ref(), ours.as_ref(), theirs.as_ref());
+
+        match merged {
+            Some(json) => files::save_value_to_json_file(&json, &output).unwrap(),
+            None if output.exists() => fs::remove_file(&output).unwrap(),
+            None => {}
+        }
+        all_conflicts.extend(conflicts.into_iter().map(|c| (label.clone(), c)));
+    }
+
+    if all_conflicts.is_empty() {
+        if let Some(conflicts_file) = conflicts_file
+            && Path::new(&conflicts_file).exists()
+        {
+            fs::remove_file(conflicts_file).unwrap();
+        }
+        println!("Merged without conflicts.");
+        return;
+    }
+
+    println!("Conflicts found, values from ours were kept:\n");
+    for (label, conflict) in &all_conflicts {
+        println!("{}: {}", label, conflict.path);
+    }
+
+    if let Some(conflicts_file) = conflicts_file {
+        let markers: Vec<String> = all_conflicts
+            .iter()
+            .map(|(label, conflict)| conflict_markers(label, conflict))
+            .collect();
+        fs::write(&conflicts_file, markers.join("\n")).unwrap();
+        println!("\nConflict markers written to: {}", conflicts_file);
+    }
+    std::process::exit(1);
+}
+
+pub fn diff_command(from: String, to: Option<String>, where_: String) {
+    println!(
+        "Comparing files in {} at {} to {}",
+        where_,
+        from,
+        to.as_deref().unwrap_or("working tree")
+    );
+
+    let old_files = git::list_files_at_revision(&from, &where_).unwrap();
+    let new_locales: BTreeMap<OsString, Value> = match &to {
+        Some(to) => {
+            let new_files = git::list_files_at_revision(to, &where_).unwrap();
+            new_files
+                .iter()
+                .filter_map(|file| file.file_name())
+                .map(|name| (name.to_owned(), load_json_at_revision(to, &new_files, name)))
+                .collect()
+        }
+        None => Catalog::open(&where_)
+            .unwrap()
+            .into_files()
+            .into_iter()
+            .filter_map(|(file, json)| Some((file.file_name()?.to_owned(), json)))
+            .collect(),
+    };
+    let names: BTreeSet<&OsStr> = old_files
+        .iter()
+        .filter_map(|file| file.file_name())
+        .chain(new_locales.keys().map(OsString::as_os_str))
+        .collect();
+    let empty = Value::Object(Default::default());
+
+    for name in names {
+        let old_json = load_json_at_revision(&from, &old_files, name);
+        let new_json = new_locales.get(name).unwrap_or(&empty);
+
+        println!("\n{}:", name.to_string_lossy());
+        print!("{}", diff_values(&old_json, new_json));
+    }
+}
+
+pub fn check_command(
+    where_: String,
+    staged: bool,
+    source: Option<String>,
+    glossary: Option<String>,
+    reference: Option<String>,
+) {
+    let glossary = glossary.map(|file| load_glossary(file).unwrap());
+    let check = |locales: &[(PathBuf, Value)], only: Option<&BTreeSet<KeyPath>>| {
+        let reference = reference_file(locales, reference.as_deref());
+        let mut problems = check_locales(locales, reference.as_deref(), only);
+        if let Some(glossary) = &glossary {
+            problems.extend(check_glossary(
+                glossary,
+                locales,
+                reference.as_deref(),
+                only,
+            ));
+        }
+        problems
+    };
+
+    if !staged {
+        println!("Checking files in {}", where_);
+        let locales = Catalog::open(&where_).unwrap().into_files();
+        exit_on_problems(check(&locales, None));
+        return;
+    }
+
+    println!("Checking staged changes in {}", where_);
+    let staged_files = git::list_staged_changes().unwrap();
+    let index_files = git::list_files_in_index(&where_).unwrap();
+    // There is no HEAD before the first commit.
+    let head_files = git::list_files_at_revision("HEAD", &where_).unwrap_or_default();
+
+    let locales: Vec<(PathBuf, Value)> = index_files
+        .iter()
+        .map(|file| {
+            let name = file.file_name().unwrap();
+            (file.clone(), load_json_at_revision("", &index_files, name))
+        })
+        .collect();
+
+    let mut touched = BTreeSet::new();
+    for file in &staged_files {
+        if !index_files.contains(file) && !head_files.contains(file) {
+            continue;
+        }
+        let name = file.file_name().unwrap();
+        let old_json = load_json_at_revision("HEAD", &head_files, name);
+        let new_json = load_json_at_revision("", &index_files, name);
+        let diff = diff_values(&old_json, &new_json);
+
+        touched.extend(diff.added);
+        touched.extend(diff.removed);
+        touched.extend(diff.renamed.into_iter().flat_map(|(from, to)| [from, to]));
+        touched.extend(diff.changed.into_iter().map(|(path, _, _)| path));
+    }
+
+    if let Some(source) = source {
+        let source: PathBuf = Path::new(&source)
+            .components()
+            .filter(|component| *component != Component::CurDir)
+            .collect();
+        let known_paths: BTreeSet<KeyPath> = locales
+            .iter()
+            .flat_map(|(_, json)| get_json_paths(json))
+            .collect();
+
+        for file in staged_files.iter().filter(|file| file.starts_with(&source)) {
+            // Only keys on added or changed lines, deleted files have none.
+            let lines = git::staged_added_lines(file).unwrap();
+            touched.extend(
+                known_paths
+                    .iter()
+                    .filter(|path| lines.iter().any(|line| mentions_key(line, path)))
+                    .cloned(),
+            );
+        }
+    }
+
+    if touched.is_empty() {
+        println!("No staged changes touch translation keys.");
+        return;
+    }
+    exit_on_problems(check(&locales, Some(&touched)));
+}
+
+pub fn blame_command(key: String, where_: String) {
+    println!("History of key '{}' in {}", key, where_);
+
+    let key_path = KeyPath::parse(&key).unwrap();
+    let catalog = Catalog::open(&where_).unwrap();
+
+    let show = |value: &Option<Value>| match value {
+        Some(value) => value.to_string(),
+        None => "(removed)".to_string(),
+    };
+
+    for locale in catalog.locales() {
+        let file = catalog.file(locale).unwrap();
+        println!("\n{}:", file.file_name().unwrap().to_string_lossy());
+
+        let changes = blame_key(file, &key_path).unwrap();
+        let current = catalog.get(locale, &key_path).unwrap().cloned();
+        let committed = changes.first().and_then(|change| change.value.clone());
+
+        if changes.is_empty() && current.is_none() {
+            println!("  not found");
+            continue;
+        }
+        if current != committed {
+            println!("  uncommitted  {}", show(&current));
+        }
+        for change in changes {
+            println!(
+                "  {}  {}  {}  {}",
+                change.commit.hash,
+                change.commit.date,
+                change.commit.author,
+                show(&change.value)
+            );
+        }
+    }
+}
+
+pub fn watch_command(where_: String, source: String) {
+    println!("Checking {} against sources in {}", where_, source);
+    watch::watch(&where_, &source).unwrap();
+}
+
+pub fn lsp_command(where_: String, reference: Option<String>) {
+    // Stdout carries the protocol, so nothing else may be printed there.
+    eprintln!("Starting language server for {}", where_);
+    lsp::run(&where_, reference).unwrap();
+}
+
+pub fn tui_command(where_: String) {
+    tui::run(&where_).unwrap();
+}
+
+pub fn serve_command(where_: String, port: u16, token: Option<String>) {
+    serve::run(&where_, port, token).unwrap();
+}
+
+pub fn codegen_command(lang: Lang, reference: String, output: String, check: bool) {
+    let generated = match lang {
+        Lang::Ts => codegen::typescript(&files::load_json_into_value(&reference).unwrap()),
+        Lang::Rust => {
+            let dir = Path::new(&reference)
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            let catalog = Catalog::open(dir).unwrap();
+            let locale = utils::get_file_stem(&reference).unwrap();
+            match codegen::rust(&catalog, &locale) {
+                Ok(generated) => generated,
+                Err(error) => return exit_on_errors(&[error]),
+            }
+        }
+    };
+    let current = fs::read_to_string(&output).ok();
+
+    if current.as_deref() == Some(generated.as_str()) {
+        println!("{} is up to date", output);
+    } else if check {
+        println!(
+            "{} is out of date, run codegen without --check to update it",
+            output
+        );
+        std::process::exit(1);
+    } else {
+        fs::write(&output, generated).unwrap();
+        println!("Generated {} from {}", output, reference);
+    }
+}
+
+pub fn schema_generate_command(reference: String, output: String) {
+    let dir = Path::new(&reference).parent().unwrap_or(Path::new(""));
+    if is_in_dir(Path::new(&output), dir) {
+        return exit_on_errors(&[schema_next_to_locales(&output)]);
+    }
+    let json = files::load_json_into_value(&reference).unwrap();
+    files::save_value_to_json_file(&generate_schema(&json), &output).unwrap();
+    println!("Generated {} from {}", output, reference);
+}
+
+pub fn schema_validate_command(schema: String, where_: String) {
+    println!("Validating files in {} against {}", where_, schema);
+    if is_in_dir(Path::new(&schema), Path::new(&where_)) {
+        return exit_on_errors(&[schema_next_to_locales(&schema)]);
+    }
+    let schema = files::load_json_into_value(&schema).unwrap();
+    let catalog = Catalog::open(&where_).unwrap();
+
+    let mut problems = Vec::new();
+    for locale in catalog.locales() {
+        let file = catalog.file(locale).unwrap();
+        let json = catalog.json(locale).unwrap();
+        problems.extend(validate_against_schema(&schema, json).into_iter().map(
+            |(path, message)| Problem {
+                file: file.to_path_buf(),
+                path,
+                message,
+            },
+        ));
+    }
+    problems.sort();
+    exit_on_problems(problems);
+}
+
+pub fn pseudo_command(where_: String, from: String, to: String, padding: u8) {
+    if from == to {
+        return exit_on_errors(&["Pseudo-locale can't overwrite reference locale!".to_string()]);
+    }
+    let catalog = Catalog::open(&where_).unwrap();
+    let file = Path::new(&where_).join(format!("{}.json", to));
+    println!("Generating {:?} from locale {}", file, from);
+
+    let pseudo = pseudo_localize(catalog.json(&from).unwrap(), padding);
+    files::save_value_to_json_file(&pseudo, &file).unwrap();
+}
+
+pub fn translate_command(
+    where_: String,
+    from: Option<String>,
+    to: String,
+    overwrite: bool,
+    provider: ProviderArgs,
+    metadata: Option<String>,
+) {
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let from = match catalog.reference(from.as_deref()) {
+        Ok(from) => from.to_string(),
+        Err(error) => return exit_on_errors(&[error.to_string()]),
+    };
+    if from == to {
+        return exit_on_errors(&["Can't translate locale to itself!".to_string()]);
+    }
+    catalog.json(&to).unwrap();
+
+    let keys: Vec<(KeyPath, String)> = get_json_paths(catalog.json(&from).unwrap())
+        .into_iter()
+        .filter(|path| overwrite || catalog.get(&to, path).unwrap().is_none())
+        .filter_map(|path| {
+            let text = catalog.get(&from, &path).unwrap()?.as_str()?.to_string();
+            Some((path, text))
+        })
+        .collect();
+    if keys.is_empty() {
+        return println!("Nothing to translate from {} to {}", from, to);
+    }
+
+    let metadata =
+        metadata.unwrap_or_else(|| format!("{}.machine.json", where_.trim_end_matches('/')));
+    let mut review = match Path::new(&metadata).exists() {
+        true => match files::load_json_into_value(&metadata) {
+            Ok(review) => review,
+            Err(error) => return exit_on_errors(&[format!("Can't parse {}: {}", metadata, error)]),
+        },
+        false => Value::Object(Default::default()),
+    };
+    let Some(reviewed) = reviewed_keys(&mut review, &to) else {
+        return exit_on_errors(&[format!(
+            "{} has to map every locale to an object of keys!",
+            metadata
+        )]);
+    };
+    println!("Translating {} texts from {} to {}", keys.len(), from, to);
+
+    let provider: Box<dyn Provider> = match provider.command {
+        Some(command) => Box::new(CommandProvider { command }),
+        None => Box::new(HttpProvider {
+            endpoint: provider.endpoint.unwrap(),
+            api: match provider.api {
+                Api::Deepl => HttpApi::Deepl,
+                Api::Libretranslate => HttpApi::LibreTranslate,
+            },
+            api_key: provider
+                .api_key
+                .or_else(|| std::env::var("SZAMBO_API_KEY").ok()),
+        }),
+    };
+    let texts: Vec<String> = keys.iter().map(|(_, text)| text.clone()).collect();
+    let translations = match translate_texts(provider.as_ref(), &texts, &from, &to) {
+        Ok(translations) => translations,
+        Err(error) => return exit_on_errors(&[error]),
+    };
+
+    for ((key, source), translation) in keys.into_iter().zip(translations) {
+        let result = translation.and_then(|text| {
+            catalog
+                .set(&to, &key, text.as_str())
+                .map(|_| text)
+                .map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(text) => {
+                println!("{}: {}", key, text);
+                reviewed.insert(key.to_string(), Value::String(source));
+            }
+            Err(error) => println!("Skipped {}: {}", key, error),
+        }
+    }
+
+    catalog.save().unwrap();
+    files::save_value_to_json_file(&review, &metadata).unwrap();
+    println!("Machine translated keys listed for review in {}", metadata);
+}
+
+pub fn fill_from_memory_command(where_: String, reference: Option<String>, threshold: u8) {
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let reference = match catalog.reference(reference.as_deref()) {
+        Ok(reference) => reference.to_string(),
+        Err(error) => return exit_on_errors(&[error.to_string()]),
+    };
+    println!(
+        "Filling missing keys in {} from translation memory of {} ({}% match or better)",
+        where_, reference, threshold
+    );
+
+    let reference_json = catalog.json(&reference).unwrap().clone();
+    let locales: Vec<String> = catalog
+        .locales()
+        .filter(|locale| *locale != reference)
+        .map(str::to_string)
+        .collect();
+    let mut filled = 0;
+
+    for locale in &locales {
+        let memory = Memory::build(&reference_json, catalog.json(locale).unwrap());
+        for path in get_json_paths(&reference_json) {
+            if catalog.get(locale, &path).unwrap().is_some() {
+                continue;
+            }
+            let Some(text) =
+                get_value_at_path(&reference_json, &path.segments()).and_then(Value::as_str)
+            else {
+                continue;
+            };
+            let Some(suggestion) = memory.suggest(text, threshold).into_iter().next() else {
+                continue;
+            };
+            match catalog.set(locale, &path, suggestion.translation.as_str()) {
+                Ok(_) => {
+                    println!(
+                        "{:?}: {}: {}",
+                        catalog.file(locale).unwrap(),
+                        path,
+                        suggestion
+                    );
+                    filled += 1;
+                }
+                Err(error) => println!("Skipped {}: {}", path, error),
+            }
+        }
+    }
+
+    catalog.save().unwrap();
+    println!("\nFilled {} keys", filled);
+}
+
+pub fn fix_shape_command(where_: String, reference: Option<String>) {
+    let mut catalog = Catalog::open(&where_).unwrap();
+    let reference = match catalog.reference(reference.as_deref()) {
+        Ok(reference) => reference.to_string(),
+        Err(Error::NoLocales) => return println!("No files found in directory: {}", where_),
+        Err(error) => return exit_on_errors(&[error.to_string()]),
+    };
+    println!(
+        "Fixing shape conflicts in {} using {} as reference",
+        where_, reference
+    );
+
+    let moved = match catalog.fix_shape(&reference) {
+        Ok(moved) => moved,
+        Err(Error::Conflicts(messages)) => return exit_on_errors(&messages),
+        Err(error) => panic!("{}", error),
+    };
+    for (locale, from, to) in &moved {
+        println!(
+            "{}: moved {} to {}",
+            catalog.file(locale).unwrap().display(),
+            from,
+            to
+        );
+    }
+    if moved.is_empty() {
+        println!("No shape conflicts found");
+    }
+
+    catalog.save().unwrap();
 }
 
 pub fn sort_command(where_: String) {
     println!("Sorting files in {}", where_);
-    let files = files::list_files_in_dir(&where_).unwrap();
-
-    files.iter().for_each(|file| {
-        let json = files::load_json_into_value(&file).unwrap();
-        files::save_value_to_json_file(&json, file).unwrap();
-    });
+    Catalog::open(&where_).unwrap().save_all().unwrap();
 }
 
 pub fn compare_command(target: String, reference: String) {
@@ -161,37 +998,67 @@ pub fn compare_command(target: String, reference: String) {
     println!("{}", result);
 }
 
-pub fn compare_all_command(where_: String) {
+pub fn compare_all_command(
+    where_: String,
+    suggest: bool,
+    glossary: Option<String>,
+    reference: Option<String>,
+) {
     println!("Comparing all files in directory: {}", where_);
 
-    let files = files::list_files_in_dir(&where_).unwrap();
+    let catalog = Catalog::open(&where_).unwrap();
+    let locales: Vec<&str> = catalog.locales().collect();
 
-    if files.len() < 2 {
+    if locales.len() < 2 {
         println!(
             "Not enough files to compare in directory: {}. Directory contains {} files",
             where_,
-            files.len()
+            locales.len()
         );
     }
 
-    let reference = files.get(0).unwrap();
-    let reference_json = files::load_json_into_value(reference).unwrap();
-
+    let reference = match catalog.reference(reference.as_deref()) {
+        Ok(reference) => reference,
+        Err(Error::NoLocales) => return,
+        Err(error) => return exit_on_errors(&[error.to_string()]),
+    };
     let mut failed = false;
 
-    files.iter().skip(1).for_each(|file| {
-        let target_json = files::load_json_into_value(file).unwrap();
-        let reference_str = reference.to_str().unwrap();
-        let target_str = file.to_str().unwrap();
-        let result = get_missing_paths(&reference_json, &target_json, &reference_str, &target_str);
+    let others = locales.iter().filter(|locale| **locale != reference);
+    for (result, locale) in catalog.compare(reference).unwrap().into_iter().zip(others) {
         println!("{}", result);
 
+        if suggest {
+            print_suggestions(&catalog, reference, locale, &result.missing_in_target);
+        }
+
         if result.is_there_any_difference() {
             failed = true;
         }
-    });
+    }
 
-    if failed == true {
+    if let Some(glossary) = glossary {
+        let glossary = load_glossary(glossary).unwrap();
+        let locales: Vec<(PathBuf, Value)> = locales
+            .iter()
+            .map(|locale| {
+                let file = catalog.file(locale).unwrap().to_path_buf();
+                (file, catalog.json(locale).unwrap().clone())
+            })
+            .collect();
+        let reference = catalog.file(reference).unwrap();
+        let problems = check_glossary(&glossary, &locales, Some(reference), None);
+
+        if !problems.is_empty() {
+            println!("Glossary violations:\n");
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            failed = true;
+        }
+    }
+
+    if failed {
         std::process::exit(1);
     }
 }
@@ -203,7 +1070,7 @@ pub fn list_unused_keys_command(translations: String, source: String) {
     );
 
     let json = files::load_json_into_value(&translations).unwrap();
-    let paths = get_json_paths(&json).unwrap();
+    let paths = get_json_paths(&json);
     let unused_paths = find_unused_paths(paths, source).unwrap();
 
     println!("Unused paths (some might be false positives!):\n",);
@@ -220,22 +1087,471 @@ pub fn remove_unused_keys_command(translations: String, source: String, where_:
     );
 
     let json = files::load_json_into_value(&translations).unwrap();
-    let paths = get_json_paths(&json).unwrap();
-    let unused_paths = find_unused_paths(paths, source).unwrap();
-    let files = files::list_files_in_dir(&where_).unwrap();
+    let paths = get_json_paths(&json);
+    let mut unused_paths = find_unused_paths(paths, source).unwrap();
+    let mut catalog = Catalog::open(&where_).unwrap();
 
     println!("Removing unused paths!");
 
-    files.iter().for_each(|file| {
-        let mut json = files::load_json_into_value(&file).unwrap();
-        for unused_path in &unused_paths {
-            let (path, key_to_remove) = utils::get_path_and_key(unused_path).unwrap();
+    unused_paths.sort_by(KeyPath::removal_order);
+    for unused_path in &unused_paths {
+        exit_on_conflicts(catalog.remove(unused_path));
+    }
+    catalog.save().unwrap();
+}
 
-            let result = remove_key_at_path(&mut json, &path, key_to_remove);
-            if let Err(message) = result {
-                println!("{} for file: {:?}", message, file);
-            }
+/// Expands `from` selector and pairs every matched key with its destination
+/// built from `to` template. Prints planned changes.
+fn map_selected_keys(
+    from: &str,
+    to: &str,
+    paths: &BTreeSet<KeyPath>,
+) -> Result<Vec<(KeyPath, KeyPath)>, String> {
+    let selector = Selector::parse(from)?;
+    let matched = selector.select(paths);
+
+    let mut pairs: Vec<(KeyPath, KeyPath)> = Vec::new();
+    for from_path in matched {
+        let to_path = selector
+            .destination(&from_path, to)
+            .map_err(|e| format!("Can't map '{}' to '{}': {}", from_path, to, e))?;
+        if let Some((other, _)) = pairs.iter().find(|(_, existing)| *existing == to_path) {
+            return Err(format!(
+                "Keys '{}' and '{}' would both end up under '{}'!",
+                other, from_path, to_path
+            ));
         }
-        files::save_value_to_json_file(&json, file).unwrap();
-    });
+        pairs.push((from_path, to_path));
+    }
+
+    if !pairs.is_empty() {
+        println!("Matched keys:\n");
+        for (from_path, to_path) in &pairs {
+            println!("{} -> {}", from_path, to_path);
+        }
+        println!();
+    }
+
+    Ok(pairs)
+}
+
+/// Creates or overwrites key in every given locale, then prints whether each
+/// key was created, updated or unchanged.
+fn upsert_into_locales(catalog: &mut Catalog, key: &KeyPath, values: Vec<(String, Value)>) {
+    let results = exit_on_conflicts(catalog.upsert(key, values));
+    catalog.save().unwrap();
+
+    for (locale, key_path, result) in results {
+        println!(
+            "{:?}: {} {}",
+            catalog.file(&locale).unwrap(),
+            key_path,
+            result
+        );
+    }
+}
+
+/// Machine translated keys of locale in review metadata, which maps locales
+/// to objects of keys and their source texts. `None` if it has other shape.
+fn reviewed_keys<'a>(review: &'a mut Value, locale: &str) -> Option<&'a mut Map<String, Value>> {
+    review
+        .as_object_mut()?
+        .entry(locale)
+        .or_insert_with(|| Value::Object(Default::default()))
+        .as_object_mut()
+}
+
+/// Whether file is directly in directory, also when file doesn't exist yet.
+fn is_in_dir(file: &Path, dir: &Path) -> bool {
+    let canonical = |dir: &Path| match dir.as_os_str().is_empty() {
+        true => fs::canonicalize("."),
+        false => fs::canonicalize(dir),
+    };
+    let parent = file.parent().unwrap_or(Path::new(""));
+    matches!((canonical(parent), canonical(dir)), (Ok(a), Ok(b)) if a == b)
+}
+
+/// Every file in a locale directory is loaded as a locale, a schema there
+/// would be one too.
+fn schema_next_to_locales(schema: &str) -> String {
+    format!(
+        "Schema {} has to be kept outside of the translations directory!",
+        schema
+    )
+}
+
+/// Files of every locale, sorted by locale.
+fn locale_files(catalog: &Catalog) -> Vec<PathBuf> {
+    catalog
+        .locales()
+        .map(|locale| catalog.file(locale).unwrap().to_path_buf())
+        .collect()
+}
+
+/// Loads JSON file, or returns `None` if it doesn't exist or is empty (git
+/// passes an empty file when there is no common ancestor).
+fn load_optional_json(path: &Path) -> Result<Option<Value>, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Can't parse {:?}: {}", path, e))
+}
+
+/// Loads file with given name from files listed at revision, the index when
+/// revision is empty. Missing file is loaded as an empty object.
+fn load_json_at_revision(revision: &str, files: &[PathBuf], name: &OsStr) -> Value {
+    let Some(file) = files.iter().find(|file| file.file_name() == Some(name)) else {
+        return Value::Object(Default::default());
+    };
+
+    let content = git::read_file_at_revision(revision, file).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+/// Formats conflict the way git does, with the common ancestor section.
+fn conflict_markers(label: &str, conflict: &Conflict) -> String {
+    let show = |value: &Option<Value>| match value {
+        Some(value) => serde_json::to_string_pretty(value).unwrap(),
+        None => "(missing)".to_string(),
+    };
+
+    format!(
+        "{}: {}\n<<<<<<< ours\n{}\n||||||| base\n{}\n=======\n{}\n>>>>>>> theirs\n",
+        label,
+        conflict.path,
+        show(&conflict.ours),
+        show(&conflict.base),
+        show(&conflict.theirs)
+    )
+}
+
+/// Lists problems and exits with error status if there are any.
+fn exit_on_problems(problems: Vec<Problem>) {
+    if problems.is_empty() {
+        println!("No problems found.");
+        return;
+    }
+
+    println!("Problems found:\n");
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    std::process::exit(1);
+}
+
+/// Picks values for selected files from update file, keyed by locale.
+fn selected_values(files: &[&PathBuf], updates: &HashMap<String, Value>) -> Vec<(String, Value)> {
+    files
+        .iter()
+        .map(|file| {
+            let locale = utils::get_file_stem(file).unwrap();
+            let value = updates.get(&locale).unwrap().clone();
+            (locale, value)
+        })
+        .collect()
+}
+
+/// Exits like `exit_on_errors` when catalog operation failed in some locales.
+fn exit_on_conflicts<T>(result: Result<T, Error>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(Error::Conflicts(messages)) => {
+            exit_on_errors(&messages);
+            std::process::exit(1)
+        }
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Prints translations from memory for keys missing in locale.
+fn print_suggestions<'a>(
+    catalog: &Catalog,
+    reference: &str,
+    locale: &str,
+    missing: impl IntoIterator<Item = &'a KeyPath>,
+) {
+    let memory = Memory::build(
+        catalog.json(reference).unwrap(),
+        catalog.json(locale).unwrap(),
+    );
+    let mut lines = Vec::new();
+    for path in missing {
+        let Some(text) = catalog
+            .get(reference, path)
+            .unwrap()
+            .and_then(Value::as_str)
+        else {
+            continue;
+        };
+        for suggestion in memory.suggest(text, SUGGESTION_SCORE).iter().take(3) {
+            lines.push(format!("{}: {}", path, suggestion));
+        }
+    }
+
+    if !lines.is_empty() {
+        println!(
+            "Suggestions for {:?} from translation memory:\n",
+            catalog.file(locale).unwrap()
+        );
+        for line in lines {
+            println!("{}", line);
+        }
+        println!();
+    }
+}
+
+/// Lists errors and exits with error status. Called before any file is
+/// written, so either every file is updated or none.
+/// File of the reference locale, see [`Catalog::reference`]. Exits when the
+/// requested locale doesn't exist.
+fn reference_file(locales: &[(PathBuf, Value)], requested: Option<&str>) -> Option<PathBuf> {
+    let catalog = Catalog::new(locales.to_vec());
+    match catalog.reference(requested) {
+        Ok(locale) => catalog.file(locale).ok().map(Path::to_path_buf),
+        Err(Error::NoLocales) => None,
+        Err(error) => {
+            exit_on_errors(&[error.to_string()]);
+            None
+        }
+    }
+}
+
+fn exit_on_errors(errors: &[String]) {
+    if errors.is_empty() {
+        return;
+    }
+
+    println!("Problems found, no files were changed:\n");
+    for error in errors {
+        println!("{}", error);
+    }
+    std::process::exit(1);
+}
+
+/// Writes locale files to an empty directory unique to the test name.
+#[cfg(test)]
+fn locale_dir(name: &str, locales: &[(&str, Value)]) -> String {
+    let dir = std::env::temp_dir().join(format!("szambo-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (file, json) in locales {
+        files::save_value_to_json_file(json, &dir.join(file)).unwrap();
+    }
+    dir.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod reviewed_keys {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_add_object_for_new_locale() {
+        let mut review = json!({ "de": { "a": "A" } });
+
+        reviewed_keys(&mut review, "sv")
+            .unwrap()
+            .insert("b".to_string(), json!("B"));
+
+        assert_eq!(review, json!({ "de": { "a": "A" }, "sv": { "b": "B" } }));
+    }
+
+    #[test]
+    fn should_return_none_for_other_shape() {
+        assert!(reviewed_keys(&mut json!([]), "sv").is_none());
+        assert!(reviewed_keys(&mut json!({ "sv": "A" }), "sv").is_none());
+    }
+}
+
+#[cfg(test)]
+mod translate_command {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_translate_only_missing_keys_by_default() {
+        let dir = locale_dir(
+            "translate",
+            &[
+                ("en.json", json!({ "a": "A", "b": "B" })),
+                ("sv.json", json!({ "a": "Ä" })),
+            ],
+        );
+        let metadata = Path::new(&dir).join("review.json");
+        let provider = ProviderArgs {
+            endpoint: None,
+            api: Api::Deepl,
+            api_key: None,
+            command: Some(r#"cat > /dev/null; echo '["Bee"]'"#.to_string()),
+        };
+
+        translate_command(
+            dir.clone(),
+            None,
+            "sv".to_string(),
+            false,
+            provider,
+            Some(metadata.to_string_lossy().to_string()),
+        );
+
+        assert_eq!(
+            files::load_json_into_value(&Path::new(&dir).join("sv.json")).unwrap(),
+            json!({ "a": "Ä", "b": "Bee" })
+        );
+        assert_eq!(
+            files::load_json_into_value(&metadata).unwrap(),
+            json!({ "sv": { "b": "B" } })
+        );
+    }
+}
+
+#[cfg(test)]
+mod is_in_dir {
+    use super::*;
+
+    #[test]
+    fn should_compare_directories_of_existing_and_new_files() {
+        let dir = locale_dir("is-in-dir", &[("en.json", serde_json::json!({}))]);
+        let dir = Path::new(&dir);
+
+        assert!(is_in_dir(&dir.join("en.json"), dir));
+        assert!(is_in_dir(&dir.join("./schema.json"), dir));
+        assert!(!is_in_dir(&dir.join("../schema.json"), dir));
+        assert!(!is_in_dir(Path::new("schema.json"), dir));
+    }
+}
+
+#[cfg(test)]
+mod load_optional_json {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_return_none_for_missing_or_empty_file() {
+        let dir = locale_dir("optional", &[]);
+        fs::write(Path::new(&dir).join("empty.json"), "\n").unwrap();
+
+        assert_eq!(
+            load_optional_json(&Path::new(&dir).join("missing.json")),
+            Ok(None)
+        );
+        assert_eq!(
+            load_optional_json(&Path::new(&dir).join("empty.json")),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn should_return_err_for_invalid_json() {
+        let dir = locale_dir("optional-invalid", &[("en.json", json!({ "a": "A" }))]);
+        let file = Path::new(&dir).join("en.json");
+        fs::write(&file, "{oops").unwrap();
+
+        let result = load_optional_json(&file);
+
+        assert!(result.unwrap_err().starts_with("Can't parse"));
+    }
+}
+
+#[cfg(test)]
+mod remove_command {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_remove_several_elements_of_one_array() {
+        let dir = locale_dir(
+            "remove",
+            &[("en.json", json!({ "months": ["Jan", "Feb", "Mar"] }))],
+        );
+
+        remove_command(r"re:^months\[[02]\]$".to_string(), dir.clone());
+
+        assert_eq!(
+            files::load_json_into_value(&Path::new(&dir).join("en.json")).unwrap(),
+            json!({ "months": ["Feb"] })
+        );
+    }
+}
+
+#[cfg(test)]
+mod rename_command {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_rename_several_elements_of_one_array() {
+        let dir = locale_dir(
+            "rename",
+            &[("en.json", json!({ "months": ["Jan", "Feb", "Mar"] }))],
+        );
+
+        rename_command(
+            r"re:^months\[(\d)\]$".to_string(),
+            "m$1".to_string(),
+            dir.clone(),
+            ConflictStrategy::Fail,
+        );
+
+        assert_eq!(
+            files::load_json_into_value(&Path::new(&dir).join("en.json")).unwrap(),
+            json!({ "months": [], "m0": "Jan", "m1": "Feb", "m2": "Mar" })
+        );
+    }
+}
+
+#[cfg(test)]
+mod move_command {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_move_several_elements_of_one_array() {
+        let dir = locale_dir(
+            "move",
+            &[("en.json", json!({ "months": ["Jan", "Feb", "Mar"] }))],
+        );
+        let to_where = locale_dir("move-to", &[]);
+
+        move_command(
+            r"re:^months\[(\d)\]$".to_string(),
+            "m$1".to_string(),
+            dir.clone(),
+            Some(to_where.clone()),
+        );
+
+        assert_eq!(
+            files::load_json_into_value(&Path::new(&dir).join("en.json")).unwrap(),
+            json!({ "months": [] })
+        );
+        assert_eq!(
+            files::load_json_into_value(&Path::new(&to_where).join("en.json")).unwrap(),
+            json!({ "m0": "Jan", "m1": "Feb", "m2": "Mar" })
+        );
+    }
+
+    #[test]
+    fn should_move_several_elements_within_directory() {
+        let dir = locale_dir(
+            "move-same",
+            &[("en.json", json!({ "months": ["Jan", "Feb", "Mar"] }))],
+        );
+
+        move_command(
+            r"re:^months\[([01])\]$".to_string(),
+            "m$1".to_string(),
+            dir.clone(),
+            None,
+        );
+
+        assert_eq!(
+            files::load_json_into_value(&Path::new(&dir).join("en.json")).unwrap(),
+            json!({ "months": ["Mar"], "m0": "Jan", "m1": "Feb" })
+        );
+    }
 }
@@ -0,0 +1,54 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::json::key_path::KeyPath;
+
+/// Error returned by [`Catalog`](crate::Catalog) operations.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// File couldn't be read or written.
+    Io {
+        file: PathBuf,
+        source: io::Error,
+    },
+    /// File isn't valid JSON.
+    Parse {
+        file: PathBuf,
+        source: serde_json::Error,
+    },
+    /// Key path or selector couldn't be parsed.
+    InvalidKey(String),
+    LocaleNotFound(String),
+    /// Directory has no locale files.
+    NoLocales,
+    KeyNotFound(KeyPath),
+    /// Operation failed in some locales, one message per problem. No locale
+    /// was changed.
+    Conflicts(Vec<String>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { file, source } => write!(f, "Can't access {:?}: {}", file, source),
+            Error::Parse { file, source } => write!(f, "Can't parse {:?}: {}", file, source),
+            Error::InvalidKey(message) => write!(f, "{}", message),
+            Error::LocaleNotFound(locale) => write!(f, "Locale '{}' not found!", locale),
+            Error::NoLocales => write!(f, "No locale files found!"),
+            Error::KeyNotFound(key) => write!(f, "Key '{}' not found!", key),
+            Error::Conflicts(messages) => write!(f, "{}", messages.join("\n")),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -8,9 +8,9 @@ use serde_json::Value;
 
 pub fn load_json_into_hash_map<P: AsRef<Path>>(
     path: P,
-) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+) -> Result<HashMap<String, Value>, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(path)?;
-    let map: HashMap<String, String> = serde_json::from_str(&content)?;
+    let map: HashMap<String, Value> = serde_json::from_str(&content)?;
     Ok(map)
 }
 
@@ -20,7 +20,9 @@ pub fn load_json_into_value<P: AsRef<Path>>(path: &P) -> Result<Value, Box<dyn s
     Ok(json)
 }
 
-pub fn list_files_in_dir<P: AsRef<Path>>(
+/// Lists locale files in directory. Other files, like conflict markers
+/// written next to them by the merge driver, are skipped.
+pub fn list_json_files_in_dir<P: AsRef<Path>>(
     path: &P,
 ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
     let mut files = Vec::new();
@@ -29,7 +31,7 @@ pub fn list_files_in_dir<P: AsRef<Path>>(
         let entry = entry?;
         let path = entry.path();
 
-        if path.is_file() {
+        if path.is_file() && is_json_file(&path) {
             files.push(path);
         }
     }
@@ -37,6 +39,11 @@ pub fn list_files_in_dir<P: AsRef<Path>>(
     Ok(files)
 }
 
+pub fn is_json_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
 pub fn save_value_to_json_file<P: AsRef<Path>>(json: &Value, path: &P) -> std::io::Result<()> {
     let mut file = File::create(path)?;
     let json_string = serde_json::to_string_pretty(json)?;
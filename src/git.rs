@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::files;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    pub author: String,
+    pub date: String,
+}
+
+/// Runs git in current directory and returns its output.
+fn run_git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Can't run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| e.to_string())
+}
+
+/// Lists JSON files directly inside directory at revision. Paths are relative to
+/// current directory, like the ones returned by `files::list_json_files_in_dir`.
+pub fn list_files_at_revision(revision: &str, dir: &str) -> Result<Vec<PathBuf>, String> {
+    let dir = format!("{}/", dir.trim_end_matches('/'));
+    let output = run_git(&["ls-tree", revision, "--", &dir])?;
+
+    // Every line is `<mode> <type> <object>\t<path>`.
+    let files = output
+        .lines()
+        .filter_map(|line| {
+            let (meta, path) = line.split_once('\t')?;
+            let is_file = meta.split_whitespace().nth(1) == Some("blob");
+            let path = PathBuf::from(path);
+            (is_file && files::is_json_file(&path)).then_some(path)
+        })
+        .collect();
+
+    Ok(files)
+}
+
+/// Lists JSON files directly inside directory in the index.
+pub fn list_files_in_index(dir: &str) -> Result<Vec<PathBuf>, String> {
+    let pattern = format!(":(glob){}/*", dir.trim_end_matches('/'));
+    let output = run_git(&["ls-files", "--", &pattern])?;
+    Ok(output
+        .lines()
+        .map(PathBuf::from)
+        .filter(|path| files::is_json_file(path))
+        .collect())
+}
+
+/// Lists files changed in the index, including deleted ones.
+pub fn list_staged_changes() -> Result<Vec<PathBuf>, String> {
+    let output = run_git(&["diff", "--cached", "--name-only", "--relative"])?;
+    Ok(output.lines().map(PathBuf::from).collect())
+}
+
+/// Returns lines added or changed in the index, without the `+` of the diff.
+pub fn staged_added_lines(path: &Path) -> Result<Vec<String>, String> {
+    let path = path.to_string_lossy();
+    let output = run_git(&["diff", "--cached", "--unified=0", "--relative", "--", &path])?;
+    Ok(output
+        .lines()
+        .filter(|line| !line.starts_with("+++"))
+        .filter_map(|line| line.strip_prefix('+'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Reads file at revision. Empty revision reads the file from the index.
+pub fn read_file_at_revision(revision: &str, path: &Path) -> Result<String, String> {
+    run_git(&["show", &format!("{}:./{}", revision, path.display())])
+}
+
+/// Lists commits that changed file, newest first.
+pub fn file_history(path: &Path) -> Result<Vec<Commit>, String> {
+    let path = path.to_string_lossy();
+    let output = run_git(&[
+        "log",
+        "--format=%h%x09%an%x09%ad",
+        "--date=short",
+        "--",
+        &path,
+    ])?;
+
+    let commits = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            Some(Commit {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(commits)
+}
@@ -0,0 +1,297 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::catalog::default_reference;
+use crate::check::Problem;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+
+/// Approved translations of reference terms, keyed by term and then locale,
+/// e.g. `{"booking": {"sv": {"approved": "bokning", "forbidden": ["reservation"]}}}`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Glossary {
+    terms: BTreeMap<String, BTreeMap<String, Term>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Term {
+    approved: String,
+    #[serde(default)]
+    forbidden: Vec<String>,
+    /// How approved and forbidden translations are found in texts.
+    #[serde(default, rename = "match")]
+    matching: Matching,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Matching {
+    /// Start of a word, so inflections match: `bokning` in `bokningar`.
+    #[default]
+    Prefix,
+    /// Whole words only: `bok` doesn't match `bokning`.
+    Word,
+    /// Anywhere, also inside compound words: `bokning` in `hotellbokning`.
+    Part,
+}
+
+/// Loads glossary file. Format is picked by extension: `.json` or
+/// `.yaml`/`.yml`.
+pub fn load_glossary<P: AsRef<Path>>(path: P) -> Result<Glossary, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| format!("Can't read {:?}: {}", path, e))?;
+
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "json" => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        "yaml" | "yml" => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+        extension => Err(format!(
+            "Unsupported glossary file extension '{}'! Use json or yaml.",
+            extension
+        )),
+    }
+}
+
+/// Checks that texts translating a glossary term of the reference locale use
+/// its approved translation and none of the forbidden ones. Without
+/// `reference` the default one is used, see [`default_reference`]. Terms match the start
+/// of a word, so inflections count too (`booking` matches `bookings`),
+/// ignoring case. Translations are matched the same way unless the term sets
+/// another `match` mode for the locale. Locales are named by file stem. When
+/// `only` is given, other keys are skipped.
+pub fn check_glossary(
+    glossary: &Glossary,
+    locales: &[(PathBuf, Value)],
+    reference: Option<&Path>,
+    only: Option<&BTreeSet<KeyPath>>,
+) -> Vec<Problem> {
+    let mut locales: Vec<&(PathBuf, Value)> = locales.iter().collect();
+    locales.sort_by(|a, b| a.0.cmp(&b.0));
+    let index = match reference {
+        Some(reference) => locales.iter().position(|(file, _)| file == reference),
+        None => default_reference(locales.iter().map(|(_, json)| get_json_paths(json).len())),
+    };
+    let Some(index) = index else {
+        return vec![];
+    };
+    let (_, reference) = locales.remove(index);
+    let others = locales;
+
+    let mut problems = Vec::new();
+    for path in get_json_paths(reference) {
+        if only.is_some_and(|only| !only.contains(&path)) {
+            continue;
+        }
+        let Some(source) = get_value_at_path(reference, &path.segments()).and_then(Value::as_str)
+        else {
+            continue;
+        };
+
+        for (term, translations) in &glossary.terms {
+            if !contains_word(source, term, Matching::Prefix) {
+                continue;
+            }
+            for (file, json) in &others {
+                let locale = file.file_stem().unwrap_or_default().to_string_lossy();
+                let Some(expected) = translations.get(locale.as_ref()) else {
+                    continue;
+                };
+                let Some(text) = get_value_at_path(json, &path.segments()).and_then(Value::as_str)
+                else {
+                    continue;
+                };
+                let mut report = |message: String| {
+                    problems.push(Problem {
+                        file: file.clone(),
+                        path: path.clone(),
+                        message,
+                    })
+                };
+
+                for forbidden in &expected.forbidden {
+                    if contains_word(text, forbidden, expected.matching) {
+                        report(format!(
+                            "uses forbidden \"{}\" for \"{}\", use \"{}\"",
+                            forbidden, term, expected.approved
+                        ));
+                    }
+                }
+                if !contains_word(text, &expected.approved, expected.matching) {
+                    report(format!(
+                        "should translate \"{}\" as \"{}\"",
+                        term, expected.approved
+                    ));
+                }
+            }
+        }
+    }
+
+    problems.sort();
+    problems
+}
+
+/// Whether text contains given word where matching allows, ignoring case.
+fn contains_word(text: &str, word: &str, matching: Matching) -> bool {
+    let text = text.to_lowercase();
+    let word = word.to_lowercase();
+    text.match_indices(&word).any(|(start, found)| {
+        let starts_word = !text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        let ends_word = !text[start + found.len()..]
+            .chars()
+            .next()
+            .is_some_and(char::is_alphanumeric);
+        match matching {
+            Matching::Prefix => starts_word,
+            Matching::Word => starts_word && ends_word,
+            Matching::Part => true,
+        }
+    })
+}
+
+#[cfg(test)]
+mod check_glossary {
+    use super::*;
+    use crate::test_support::messages;
+    use serde_json::json;
+
+    fn glossary() -> Glossary {
+        serde_json::from_value(json!({
+            "booking": { "sv": { "approved": "bokning", "forbidden": ["reservation"] } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn should_accept_approved_translation_and_its_inflections() {
+        let locales = vec![
+            (PathBuf::from("en.json"), json!({ "a": "Your Bookings" })),
+            (PathBuf::from("sv.json"), json!({ "a": "Dina bokningar" })),
+        ];
+
+        let result = check_glossary(&glossary(), &locales, None, None);
+
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn should_report_forbidden_and_missing_approved_translation() {
+        let locales = vec![
+            (
+                PathBuf::from("sv.json"),
+                json!({ "a": "Din reservation", "b": "Rebooking" }),
+            ),
+            (
+                PathBuf::from("en.json"),
+                json!({ "a": "Your booking", "b": "Rebooking" }),
+            ),
+        ];
+
+        let result = check_glossary(&glossary(), &locales, None, None);
+
+        assert_eq!(
+            messages(result),
+            vec![
+                r#""sv.json": a: should translate "booking" as "bokning""#,
+                r#""sv.json": a: uses forbidden "reservation" for "booking", use "bokning""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_check_terms_of_given_reference() {
+        let locales = vec![
+            (PathBuf::from("de.json"), json!({ "a": "Ihre Buchung" })),
+            (PathBuf::from("en.json"), json!({ "a": "Your booking" })),
+            (PathBuf::from("sv.json"), json!({ "a": "Din reservation" })),
+        ];
+
+        let result = check_glossary(&glossary(), &locales, Some(Path::new("en.json")), None);
+
+        assert_eq!(
+            messages(result),
+            vec![
+                r#""sv.json": a: should translate "booking" as "bokning""#,
+                r#""sv.json": a: uses forbidden "reservation" for "booking", use "bokning""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_default_to_reference_with_most_keys() {
+        let locales = vec![
+            (PathBuf::from("de.json"), json!({ "a": "Ihre Buchung" })),
+            (
+                PathBuf::from("en.json"),
+                json!({ "a": "Your booking", "b": "Bookings" }),
+            ),
+            (
+                PathBuf::from("sv.json"),
+                json!({ "a": "Din bokning", "b": "Reservationer" }),
+            ),
+        ];
+
+        let result = check_glossary(&glossary(), &locales, None, None);
+
+        assert_eq!(
+            messages(result),
+            vec![
+                r#""sv.json": b: should translate "booking" as "bokning""#,
+                r#""sv.json": b: uses forbidden "reservation" for "booking", use "bokning""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_match_inside_compound_words_when_configured() {
+        let glossary: Glossary = serde_json::from_value(json!({
+            "booking": { "sv": {
+                "approved": "bokning", "forbidden": ["reservation"], "match": "part"
+            } }
+        }))
+        .unwrap();
+        let locales = vec![
+            (
+                PathBuf::from("en.json"),
+                json!({ "a": "Hotel booking", "b": "Hotel booking" }),
+            ),
+            (
+                PathBuf::from("sv.json"),
+                json!({ "a": "Hotellbokning", "b": "Hotellreservation" }),
+            ),
+        ];
+
+        let result = check_glossary(&glossary, &locales, None, None);
+
+        assert_eq!(
+            messages(result),
+            vec![
+                r#""sv.json": b: should translate "booking" as "bokning""#,
+                r#""sv.json": b: uses forbidden "reservation" for "booking", use "bokning""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_match_whole_words_only_when_configured() {
+        let glossary: Glossary = serde_json::from_value(json!({
+            "booking": { "sv": { "approved": "bokning", "forbidden": ["bok"], "match": "word" } }
+        }))
+        .unwrap();
+        let locales = vec![
+            (PathBuf::from("en.json"), json!({ "a": "Your booking" })),
+            (PathBuf::from("sv.json"), json!({ "a": "Din bokning" })),
+        ];
+
+        let result = check_glossary(&glossary, &locales, None, None);
+
+        assert_eq!(result, vec![]);
+    }
+}
@@ -1,18 +1,28 @@
+use crate::json::key_path::KeyPath;
+use crate::json::shape::{ShapeConflict, find_shape_conflicts};
 use serde_json::Value;
 use std::collections::BTreeSet;
 use std::fmt;
 
+/// Differences between keys of two locale files. New kinds of differences
+/// may be added, so it can't be built outside of this crate.
 #[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct JsonDiff {
     pub target_file: String,
     pub reference_file: String,
-    pub missing_in_target: Vec<String>,
-    pub missing_in_reference: Vec<String>,
+    pub missing_in_target: Vec<KeyPath>,
+    pub missing_in_reference: Vec<KeyPath>,
+    /// Keys holding a value of different type, e.g. text in target where
+    /// reference has an object. Keys under them aren't reported as missing.
+    pub shape_conflicts: Vec<ShapeConflict>,
 }
 
 impl JsonDiff {
     pub fn is_there_any_difference(&self) -> bool {
-        self.missing_in_target.len() > 0 || self.missing_in_reference.len() > 0
+        !self.missing_in_target.is_empty()
+            || !self.missing_in_reference.is_empty()
+            || !self.shape_conflicts.is_empty()
     }
 }
 
@@ -36,6 +46,13 @@ impl fmt::Display for JsonDiff {
             }
         }
 
+        if !self.shape_conflicts.is_empty() {
+            writeln!(f, "\nShape conflicts:\n")?;
+            for conflict in &self.shape_conflicts {
+                writeln!(f, "{}", conflict)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -49,16 +66,25 @@ pub fn get_missing_paths(
     let mut reference_paths = BTreeSet::new();
     let mut target_paths = BTreeSet::new();
 
-    collect_paths(reference_json, String::new(), &mut reference_paths);
-    collect_paths(target_json, String::new(), &mut target_paths);
+    collect_paths(reference_json, KeyPath::default(), &mut reference_paths);
+    collect_paths(target_json, KeyPath::default(), &mut target_paths);
+
+    let shape_conflicts = find_shape_conflicts(reference_json, target_json);
+    let is_conflicting = |path: &KeyPath| {
+        shape_conflicts
+            .iter()
+            .any(|conflict| path.starts_with(&conflict.path))
+    };
 
     let missing_in_target = reference_paths
         .difference(&target_paths)
+        .filter(|path| !is_conflicting(path))
         .cloned()
         .collect::<Vec<_>>();
 
     let missing_in_reference = target_paths
         .difference(&reference_paths)
+        .filter(|path| !is_conflicting(path))
         .cloned()
         .collect::<Vec<_>>();
 
@@ -67,19 +93,20 @@ pub fn get_missing_paths(
         reference_file: reference_path.to_owned(),
         missing_in_target,
         missing_in_reference,
+        shape_conflicts,
     }
 }
 
-fn collect_paths(value: &Value, current_path: String, paths: &mut BTreeSet<String>) {
+fn collect_paths(value: &Value, current_path: KeyPath, paths: &mut BTreeSet<KeyPath>) {
     match value {
         Value::Object(map) => {
             for (key, val) in map {
-                let new_path = if current_path.is_empty() {
-                    key.clone()
-                } else {
-                    format!("{}.{}", current_path, key)
-                };
-                collect_paths(val, new_path, paths);
+                collect_paths(val, current_path.child(key), paths);
+            }
+        }
+        Value::Array(items) => {
+            for (i, val) in items.iter().enumerate() {
+                collect_paths(val, current_path.index(i), paths);
             }
         }
         _ => {
@@ -91,6 +118,7 @@ fn collect_paths(value: &Value, current_path: String, paths: &mut BTreeSet<Strin
 #[cfg(test)]
 mod get_missing_paths {
     use super::*;
+    use crate::test_support::key;
     use serde_json::json;
 
     #[test]
@@ -102,6 +130,7 @@ mod get_missing_paths {
             missing_in_target: vec![],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
         };
 
         let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
@@ -128,6 +157,7 @@ mod get_missing_paths {
             missing_in_target: vec![],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
         };
 
         let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
@@ -141,9 +171,10 @@ mod get_missing_paths {
         let target = json!({});
         let expected = JsonDiff {
             missing_in_reference: vec![],
-            missing_in_target: vec!["key".to_owned()],
+            missing_in_target: vec![key("key")],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
         };
 
         let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
@@ -156,10 +187,11 @@ mod get_missing_paths {
         let reference = json!({});
         let target = json!({ "key": "value" });
         let expected = JsonDiff {
-            missing_in_reference: vec!["key".to_owned()],
+            missing_in_reference: vec![key("key")],
             missing_in_target: vec![],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
         };
 
         let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
@@ -182,9 +214,10 @@ mod get_missing_paths {
         });
         let expected = JsonDiff {
             missing_in_reference: vec![],
-            missing_in_target: vec!["foo.baz".to_owned()],
+            missing_in_target: vec![key("foo.baz")],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
         };
 
         let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
@@ -206,10 +239,11 @@ mod get_missing_paths {
             }
         });
         let expected = JsonDiff {
-            missing_in_reference: vec!["foo.baz".to_owned()],
+            missing_in_reference: vec![key("foo.baz")],
             missing_in_target: vec![],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
         };
 
         let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
@@ -232,29 +266,104 @@ mod get_missing_paths {
             }
         });
         let expected = JsonDiff {
-            missing_in_reference: vec!["foo.baz".to_owned()],
-            missing_in_target: vec!["key".to_owned()],
+            missing_in_reference: vec![key("foo.baz")],
+            missing_in_target: vec![key("key")],
+            target_file: "target.json".to_owned(),
+            reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
+        };
+
+        let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_not_confuse_dotted_keys_with_nested_keys() {
+        let reference = json!({
+            "errors": {
+                "file": {
+                    "tooLarge": "value"
+                }
+            }
+        });
+        let target = json!({
+            "errors": {
+                "file.tooLarge": "value"
+            }
+        });
+        let expected = JsonDiff {
+            missing_in_reference: vec![key("errors.file\\.tooLarge")],
+            missing_in_target: vec![key("errors.file.tooLarge")],
+            target_file: "target.json".to_owned(),
+            reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
+        };
+
+        let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_return_json_diff_where_array_lengths_differ() {
+        let reference = json!({
+            "months": ["January", "February", "March"],
+            "count": 3
+        });
+        let target = json!({
+            "months": ["Januari", "Februari"],
+            "count": 3
+        });
+        let expected = JsonDiff {
+            missing_in_reference: vec![],
+            missing_in_target: vec![key("months[2]")],
+            target_file: "target.json".to_owned(),
+            reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
+        };
+
+        let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_report_shape_conflict_instead_of_missing_keys() {
+        let reference = json!({ "pax": { "rooms": "Rooms" } });
+        let target = json!({ "pax": "Resenärer" });
+        let expected = JsonDiff {
+            missing_in_reference: vec![],
+            missing_in_target: vec![],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![ShapeConflict {
+                path: KeyPath::parse("pax").unwrap(),
+                reference: "object",
+                target: "string",
+            }],
         };
 
         let result = get_missing_paths(&reference, &target, "reference.json", "target.json");
 
         assert_eq!(result, expected);
+        assert!(result.is_there_any_difference());
     }
 }
 
 #[cfg(test)]
 mod json_diff {
     use super::*;
+    use crate::test_support::key;
 
     #[test]
     fn should_print_json_diff_in_human_readable_format() {
         let json_diff = JsonDiff {
-            missing_in_reference: vec!["foo".to_owned()],
-            missing_in_target: vec!["bar".to_owned()],
+            missing_in_reference: vec![key("foo")],
+            missing_in_target: vec![key("bar")],
             target_file: "target.json".to_owned(),
             reference_file: "reference.json".to_owned(),
+            shape_conflicts: vec![],
         };
         let expected = "\nMissing in target (target.json):\n\nbar\n\nMissing in reference (reference.json):\n\nfoo\n".to_owned();
 
@@ -0,0 +1,81 @@
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::rename::insert_value_at_path;
+use serde_json::Value;
+
+pub fn copy_key_at_path(
+    json: &mut Value,
+    from_path: &KeyPath,
+    to_path: &KeyPath,
+) -> Result<(), String> {
+    let value = get_value_at_path(json, &from_path.segments())
+        .cloned()
+        .ok_or_else(|| format!("Key '{}' not found during copy!", from_path))?;
+
+    insert_value_at_path(json, &to_path.segments(), value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod copy_key_at_path {
+    use super::*;
+    use crate::test_support::key;
+    use serde_json::json;
+
+    #[test]
+    fn should_copy_key_at_path() {
+        let mut data = json!({
+            "key": "value"
+        });
+        let expected = json!({
+            "key": "value",
+            "new_key": "value"
+        });
+
+        let result = copy_key_at_path(&mut data, &key("key"), &key("new_key"));
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_copy_whole_object_to_new_parent() {
+        let mut data = json!({
+            "pax": {
+                "rooms": "value",
+                "adults": "value"
+            }
+        });
+        let expected = json!({
+            "pax": {
+                "rooms": "value",
+                "adults": "value"
+            },
+            "booking": {
+                "pax": {
+                    "rooms": "value",
+                    "adults": "value"
+                }
+            }
+        });
+
+        let result = copy_key_at_path(&mut data, &key("pax"), &key("booking.pax"));
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_return_err_if_from_path_does_not_exist_in_file() {
+        let mut data = json!({
+            "foo": "value"
+        });
+        let expected = json!({
+            "foo": "value"
+        });
+
+        let result = copy_key_at_path(&mut data, &key("foo.bar"), &key("baz"));
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap_err(), "Key 'foo.bar' not found during copy!");
+    }
+}
@@ -0,0 +1,202 @@
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// How similar values of a removed and an added key must be to report them
+/// as a rename.
+const RENAME_SIMILARITY: f64 = 0.8;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct LocaleDiff {
+    pub added: Vec<KeyPath>,
+    pub removed: Vec<KeyPath>,
+    pub renamed: Vec<(KeyPath, KeyPath)>,
+    pub changed: Vec<(KeyPath, Value, Value)>,
+}
+
+impl LocaleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for LocaleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "  no changes");
+        }
+        for path in &self.added {
+            writeln!(f, "  added    {}", path)?;
+        }
+        for path in &self.removed {
+            writeln!(f, "  removed  {}", path)?;
+        }
+        for (from, to) in &self.renamed {
+            writeln!(f, "  renamed  {} -> {}", from, to)?;
+        }
+        for (path, old, new) in &self.changed {
+            writeln!(f, "  changed  {}: {} -> {}", path, old, new)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two versions of a locale file. Removed and added keys with
+/// similar values are paired up as renames.
+pub fn diff_values(old: &Value, new: &Value) -> LocaleDiff {
+    let old_paths: BTreeSet<KeyPath> = get_json_paths(old).into_iter().collect();
+    let new_paths: BTreeSet<KeyPath> = get_json_paths(new).into_iter().collect();
+    let value = |json, path: &KeyPath| get_value_at_path(json, &path.segments()).cloned();
+
+    let mut diff = LocaleDiff::default();
+
+    for path in old_paths.intersection(&new_paths) {
+        let (old_value, new_value) = (value(old, path), value(new, path));
+        if old_value != new_value {
+            diff.changed.push((
+                path.clone(),
+                old_value.unwrap_or_default(),
+                new_value.unwrap_or_default(),
+            ));
+        }
+    }
+
+    let removed: Vec<&KeyPath> = old_paths.difference(&new_paths).collect();
+    let added: Vec<&KeyPath> = new_paths.difference(&old_paths).collect();
+
+    let mut candidates = Vec::new();
+    for (i, from) in removed.iter().enumerate() {
+        for (j, to) in added.iter().enumerate() {
+            let score = similarity(&value(old, from), &value(new, to));
+            if score >= RENAME_SIMILARITY {
+                candidates.push((score, i, j));
+            }
+        }
+    }
+    // Best matches first, ties keep path order.
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+    let mut renamed_from = vec![false; removed.len()];
+    let mut renamed_to = vec![false; added.len()];
+    for (_, i, j) in candidates {
+        if !renamed_from[i] && !renamed_to[j] {
+            renamed_from[i] = true;
+            renamed_to[j] = true;
+            diff.renamed.push((removed[i].clone(), added[j].clone()));
+        }
+    }
+    diff.renamed.sort();
+
+    diff.removed = removed
+        .into_iter()
+        .zip(renamed_from)
+        .filter(|(_, renamed)| !renamed)
+        .map(|(path, _)| path.clone())
+        .collect();
+    diff.added = added
+        .into_iter()
+        .zip(renamed_to)
+        .filter(|(_, renamed)| !renamed)
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    diff
+}
+
+/// Returns 1.0 for equal values and, for strings, 1 minus edit distance
+/// relative to the longer one.
+fn similarity(a: &Option<Value>, b: &Option<Value>) -> f64 {
+    match (a, b) {
+        _ if a == b => 1.0,
+        (Some(Value::String(a)), Some(Value::String(b))) => {
+            let a: Vec<char> = a.chars().collect();
+            let b: Vec<char> = b.chars().collect();
+            let longest = a.len().max(b.len());
+            1.0 - edit_distance(&a, &b) as f64 / longest as f64
+        }
+        _ => 0.0,
+    }
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod diff_values {
+    use super::*;
+    use serde_json::json;
+
+    fn path(input: &str) -> KeyPath {
+        KeyPath::parse(input).unwrap()
+    }
+
+    #[test]
+    fn should_report_added_removed_and_changed_keys() {
+        let old = json!({ "a": "Apple", "b": "Banana", "c": "Cherry" });
+        let new = json!({ "a": "Apple", "b": "Blueberry", "d": "Date" });
+
+        let result = diff_values(&old, &new);
+
+        assert_eq!(result.added, vec![path("d")]);
+        assert_eq!(result.removed, vec![path("c")]);
+        assert_eq!(result.renamed, vec![]);
+        assert_eq!(
+            result.changed,
+            vec![(path("b"), json!("Banana"), json!("Blueberry"))]
+        );
+    }
+
+    #[test]
+    fn should_report_rename_of_key_with_same_value() {
+        let old = json!({ "pax": { "kids": "Child | Children" } });
+        let new = json!({ "pax": { "children": "Child | Children" } });
+
+        let result = diff_values(&old, &new);
+
+        assert_eq!(
+            result.renamed,
+            vec![(path("pax.kids"), path("pax.children"))]
+        );
+        assert!(result.added.is_empty());
+        assert!(result.removed.is_empty());
+    }
+
+    #[test]
+    fn should_report_rename_of_key_with_slightly_changed_value() {
+        let old = json!({ "add_room": "Add room" });
+        let new = json!({ "room": { "add": "Add rooms" }, "other": "Something else" });
+
+        let result = diff_values(&old, &new);
+
+        assert_eq!(result.renamed, vec![(path("add_room"), path("room.add"))]);
+        assert_eq!(result.added, vec![path("other")]);
+    }
+
+    #[test]
+    fn should_report_nothing_for_reordered_keys() {
+        let old: Value = serde_json::from_str(r#"{ "b": "B", "a": "A" }"#).unwrap();
+        let new: Value = serde_json::from_str(r#"{ "a": "A", "b": "B" }"#).unwrap();
+
+        let result = diff_values(&old, &new);
+
+        assert!(result.is_empty());
+    }
+}
@@ -1,24 +1,32 @@
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::{ensure_path_is_free, get_child_mut, get_value_at_path};
+use crate::json::paths::get_json_paths;
+use crate::json::rename::insert_value_at_path;
 use serde_json::Value;
 
 pub fn insert_under_key(
     json: &mut Value,
     path: &[&str],
     new_key: &str,
-    new_value: &str,
+    new_value: impl Into<Value>,
 ) -> Result<(), String> {
     let mut current = json;
 
     for (i, key) in path.iter().enumerate() {
-        let obj = current.as_object_mut().ok_or_else(|| {
-            format!(
-                "Expected object at path segment '{}', but found something else!",
-                key
-            )
-        })?;
-
-        current = obj.entry(*key).or_insert(Value::Object(Default::default()));
-
-        if !current.is_object() && i != path.len() - 1 {
+        current = if current.is_array() {
+            get_child_mut(current, key)
+                .ok_or_else(|| format!("Index '{}' is out of bounds!", key))?
+        } else {
+            let obj = current.as_object_mut().ok_or_else(|| {
+                format!(
+                    "Expected object at path segment '{}', but found something else!",
+                    key
+                )
+            })?;
+            obj.entry(*key).or_insert(Value::Object(Default::default()))
+        };
+
+        if !current.is_object() && !current.is_array() && i != path.len() - 1 {
             return Err(format!(
                 "Expected object at path segment '{}', but found something else!",
                 key
@@ -34,7 +42,23 @@ pub fn insert_under_key(
                     new_key
                 ));
             }
-            obj.insert(new_key.to_string(), Value::String(new_value.to_string()));
+            obj.insert(new_key.to_string(), new_value.into());
+            Ok(())
+        }
+        Value::Array(items) => {
+            let index: usize = new_key.parse().map_err(|_| {
+                format!("Expected index to insert into array, found '{}'!", new_key)
+            })?;
+            if index < items.len() {
+                return Err(format!(
+                    "Index '{}' already exists at the target path! Use REPLACE command instead.",
+                    index
+                ));
+            }
+            if index > items.len() {
+                return Err(format!("Index '{}' is out of bounds!", index));
+            }
+            items.push(new_value.into());
             Ok(())
         }
         _ => Err(
@@ -43,6 +67,44 @@ pub fn insert_under_key(
     }
 }
 
+/// Inserts value under key like `insert_under_key`, but an object value is
+/// merged into object already present under key. Existing values are never
+/// replaced, every leaf which is already taken is reported and nothing is
+/// inserted then.
+pub fn insert_tree_under_key(
+    json: &mut Value,
+    target: &KeyPath,
+    new_value: Value,
+) -> Result<(), Vec<String>> {
+    if !new_value.is_object() {
+        let (path, new_key) = target.parent_and_key().map_err(|e| vec![e])?;
+        return insert_under_key(json, &path, new_key, new_value).map_err(|e| vec![e]);
+    }
+
+    let leaves = get_json_paths(&new_value);
+    let mut conflicts = Vec::new();
+
+    for leaf in &leaves {
+        if let Err(message) = ensure_path_is_free(json, &target.join(leaf)) {
+            conflicts.push(message);
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+
+    for leaf in &leaves {
+        let value = get_value_at_path(&new_value, &leaf.segments()).cloned();
+        if let Some(value) = value {
+            insert_value_at_path(json, &target.join(leaf).segments(), value)
+                .map_err(|e| vec![e.to_string()])?;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod insert_under_key {
     use super::*;
@@ -138,6 +200,51 @@ mod insert_under_key {
         assert_eq!(result.unwrap(), ());
     }
 
+    #[test]
+    fn should_insert_non_string_values() {
+        let mut data = json!({});
+        let expected = json!({
+            "months": ["January", "February"],
+            "count": 12,
+            "enabled": true,
+            "empty": null
+        });
+
+        insert_under_key(&mut data, &[], "months", json!(["January", "February"])).unwrap();
+        insert_under_key(&mut data, &[], "count", 12).unwrap();
+        insert_under_key(&mut data, &[], "enabled", true).unwrap();
+        let result = insert_under_key(&mut data, &[], "empty", Value::Null);
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_append_value_to_array_at_next_index() {
+        let mut data = json!({
+            "months": ["January"]
+        });
+        let expected = json!({
+            "months": ["January", "February"]
+        });
+
+        let result = insert_under_key(&mut data, &["months"], "1", "February");
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_return_error_if_array_index_is_out_of_bounds() {
+        let mut data = json!({
+            "months": ["January"]
+        });
+
+        let result = insert_under_key(&mut data, &["months"], "5", "June");
+
+        assert_eq!(result.unwrap_err(), "Index '5' is out of bounds!");
+    }
+
     #[test]
     fn should_return_error_if_there_is_object_under_key() {
         let mut data = json!({
@@ -213,3 +320,100 @@ mod insert_under_key {
         );
     }
 }
+
+#[cfg(test)]
+mod insert_tree_under_key {
+    use super::*;
+    use crate::test_support::key;
+    use serde_json::json;
+
+    #[test]
+    fn should_insert_object_if_key_does_not_exist() {
+        let mut data = json!({});
+        let expected = json!({
+            "feature": {
+                "title": "Title",
+                "body": "Body"
+            }
+        });
+
+        let result = insert_tree_under_key(
+            &mut data,
+            &key("feature"),
+            json!({ "title": "Title", "body": "Body" }),
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_merge_object_and_keep_existing_siblings() {
+        let mut data = json!({
+            "feature": {
+                "title": "Title",
+                "nested": {
+                    "a": "a_value"
+                }
+            }
+        });
+        let expected = json!({
+            "feature": {
+                "title": "Title",
+                "body": "Body",
+                "nested": {
+                    "a": "a_value",
+                    "b": "b_value"
+                }
+            }
+        });
+
+        let result = insert_tree_under_key(
+            &mut data,
+            &key("feature"),
+            json!({ "body": "Body", "nested": { "b": "b_value" } }),
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_return_every_conflicting_leaf_and_leave_file_intact() {
+        let mut data = json!({
+            "feature": {
+                "title": "Title",
+                "nested": "text"
+            }
+        });
+        let expected = data.clone();
+
+        let result = insert_tree_under_key(
+            &mut data,
+            &key("feature"),
+            json!({ "title": "Other", "body": "Body", "nested": { "b": "b_value" } }),
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(
+            result.unwrap_err(),
+            vec![
+                "Expected object at path segment 'nested' of 'feature.nested.b', but found something else!"
+                    .to_owned(),
+                "Key 'feature.title' already exists!".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_insert_string_like_insert_under_key() {
+        let mut data = json!({ "key": "value" });
+
+        let result = insert_tree_under_key(&mut data, &key("key"), json!("other"));
+
+        assert_eq!(
+            result.unwrap_err(),
+            vec!["Key 'key' already exists at the target path! Use REPLACE command instead."]
+        );
+    }
+}
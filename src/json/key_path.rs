@@ -0,0 +1,383 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Path to a value inside a translation file, e.g. `errors.file\.tooLarge`,
+/// `errors["file.tooLarge"]` or `months[0]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyPath {
+    segments: Vec<Segment>,
+}
+
+/// Object key or array index. Index is kept as text, so both can be passed
+/// around as `&str`; it only changes how the path is displayed and sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Segment {
+    name: String,
+    is_index: bool,
+}
+
+impl Segment {
+    fn key(name: String) -> Self {
+        Segment {
+            name,
+            is_index: false,
+        }
+    }
+}
+
+impl Ord for Segment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.is_index && other.is_index {
+            // Indexes have no leading zeros, so shorter means smaller.
+            (self.name.len(), &self.name).cmp(&(other.name.len(), &other.name))
+        } else {
+            (&self.name, self.is_index).cmp(&(&other.name, other.is_index))
+        }
+    }
+}
+
+impl PartialOrd for Segment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl KeyPath {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| format!("Invalid key path '{}': {}!", input, reason);
+
+        if input.is_empty() {
+            return Err(invalid("path is empty"));
+        }
+
+        let mut segments = Vec::new();
+        let mut current = String::new();
+        let mut chars = input.chars();
+        // Set right after `]`, when only `.`, `[` or the end of input may follow.
+        let mut closed = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if !closed {
+                        if current.is_empty() {
+                            return Err(invalid("empty segment"));
+                        }
+                        segments.push(Segment::key(std::mem::take(&mut current)));
+                    }
+                    closed = false;
+                }
+                '[' => {
+                    if !current.is_empty() {
+                        segments.push(Segment::key(std::mem::take(&mut current)));
+                    } else if !closed && !segments.is_empty() {
+                        return Err(invalid("empty segment"));
+                    }
+
+                    let quote = match chars.next() {
+                        Some(q @ ('"' | '\'')) => q,
+                        Some(d) if d.is_ascii_digit() => {
+                            let mut digits = d.to_string();
+                            loop {
+                                match chars.next() {
+                                    Some(']') => break,
+                                    Some(d) if d.is_ascii_digit() => digits.push(d),
+                                    _ => return Err(invalid("expected ']' after index")),
+                                }
+                            }
+                            let index: usize =
+                                digits.parse().map_err(|_| invalid("index is too large"))?;
+                            segments.push(Segment {
+                                name: index.to_string(),
+                                is_index: true,
+                            });
+                            closed = true;
+                            continue;
+                        }
+                        _ => return Err(invalid("expected quote or index after '['")),
+                    };
+
+                    let mut key = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('\\') => {
+                                key.push(chars.next().ok_or_else(|| invalid("dangling escape"))?)
+                            }
+                            Some(q) if q == quote => break,
+                            Some(c) => key.push(c),
+                            None => return Err(invalid("unterminated quote")),
+                        }
+                    }
+
+                    if chars.next() != Some(']') {
+                        return Err(invalid("expected ']' after closing quote"));
+                    }
+
+                    segments.push(Segment::key(key));
+                    closed = true;
+                }
+                '\\' if !closed => {
+                    current.push(chars.next().ok_or_else(|| invalid("dangling escape"))?);
+                }
+                c if !closed => current.push(c),
+                _ => return Err(invalid("expected '.' or '[' after ']'")),
+            }
+        }
+
+        if !closed {
+            if current.is_empty() {
+                return Err(invalid("empty segment"));
+            }
+            segments.push(Segment::key(current));
+        }
+
+        Ok(KeyPath { segments })
+    }
+
+    pub fn segments(&self) -> Vec<&str> {
+        self.segments.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Splits path into parent segments and the last key, as expected by
+    /// `insert_under_key`, `replace_value_at_key` and `remove_key_at_path`.
+    pub fn parent_and_key(&self) -> Result<(Vec<&str>, &str), String> {
+        match self.segments.split_last() {
+            Some((key, parent)) => {
+                Ok((parent.iter().map(|s| s.name.as_str()).collect(), &key.name))
+            }
+            None => Err("Key path is empty!".to_string()),
+        }
+    }
+
+    pub fn child(&self, key: &str) -> KeyPath {
+        let mut segments = self.segments.clone();
+        segments.push(Segment::key(key.to_string()));
+        KeyPath { segments }
+    }
+
+    pub fn index(&self, index: usize) -> KeyPath {
+        let mut segments = self.segments.clone();
+        segments.push(Segment {
+            name: index.to_string(),
+            is_index: true,
+        });
+        KeyPath { segments }
+    }
+
+    /// Path without its last segment, empty for a top-level key.
+    pub fn parent(&self) -> KeyPath {
+        let mut segments = self.segments.clone();
+        segments.pop();
+        KeyPath { segments }
+    }
+
+    /// Path with segments of other path appended.
+    pub fn join(&self, other: &KeyPath) -> KeyPath {
+        let mut segments = self.segments.clone();
+        segments.extend(other.segments.iter().cloned());
+        KeyPath { segments }
+    }
+
+    /// Order in which paths can be removed one by one. Same as `Ord`, except
+    /// later elements of an array come first, so removing one doesn't shift
+    /// indexes of those still to go.
+    pub fn removal_order(&self, other: &KeyPath) -> Ordering {
+        for (a, b) in self.segments.iter().zip(&other.segments) {
+            match a.cmp(b) {
+                Ordering::Equal => continue,
+                ordering if a.is_index && b.is_index => return ordering.reverse(),
+                ordering => return ordering,
+            }
+        }
+        self.segments.len().cmp(&other.segments.len())
+    }
+
+    pub fn starts_with(&self, prefix: &KeyPath) -> bool {
+        self.segments.starts_with(&prefix.segments)
+    }
+
+    /// Path joined with plain dots, the way it is usually written in source code.
+    pub fn to_dotted(&self) -> String {
+        self.segments().join(".")
+    }
+}
+
+impl FromStr for KeyPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyPath::parse(s)
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if segment.is_index {
+                write!(f, "[{}]", segment.name)?;
+                continue;
+            }
+            if segment.name.is_empty() {
+                write!(f, "[\"\"]")?;
+                continue;
+            }
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", escape_segment(&segment.name))?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes characters, which would otherwise be read as path syntax.
+pub fn escape_segment(segment: &str) -> String {
+    let mut escaped = String::with_capacity(segment.len());
+    for c in segment.chars() {
+        if matches!(c, '.' | '[' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod parse_and_display {
+    use super::*;
+
+    fn segments(input: &str) -> Vec<String> {
+        let path = KeyPath::parse(input).unwrap();
+        path.segments().iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn should_split_plain_path_on_dots() {
+        assert_eq!(segments("foo.bar.baz"), vec!["foo", "bar", "baz"]);
+        assert_eq!(segments("foo"), vec!["foo"]);
+    }
+
+    #[test]
+    fn should_keep_escaped_dots_inside_segment() {
+        assert_eq!(
+            segments("errors.file\\.tooLarge"),
+            vec!["errors", "file.tooLarge"]
+        );
+        assert_eq!(segments("a\\\\b"), vec!["a\\b"]);
+    }
+
+    #[test]
+    fn should_parse_bracket_notation() {
+        let cases = [
+            ("errors[\"file.tooLarge\"]", vec!["errors", "file.tooLarge"]),
+            ("errors['file.tooLarge']", vec!["errors", "file.tooLarge"]),
+            ("[\"a.b\"].c", vec!["a.b", "c"]),
+            ("a[\"b\"][\"c\"].d", vec!["a", "b", "c", "d"]),
+            ("a[\"say \\\"hi\\\"\"]", vec!["a", "say \"hi\""]),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(segments(input), expected, "Failed on input: {}", input);
+        }
+    }
+
+    #[test]
+    fn should_return_err_for_malformed_paths() {
+        let cases = [
+            ("", "path is empty"),
+            ("foo..bar", "empty segment"),
+            (".foo", "empty segment"),
+            ("foo.", "empty segment"),
+            ("foo.[\"bar\"]", "empty segment"),
+            ("foo[bar]", "expected quote or index after '['"),
+            ("foo[1a]", "expected ']' after index"),
+            ("foo[\"bar", "unterminated quote"),
+            ("foo[\"bar\"", "expected ']' after closing quote"),
+            ("foo[\"bar\"]baz", "expected '.' or '[' after ']'"),
+            ("foo\\", "dangling escape"),
+        ];
+
+        for (input, reason) in cases {
+            assert_eq!(
+                KeyPath::parse(input).unwrap_err(),
+                format!("Invalid key path '{}': {}!", input, reason),
+                "Failed on input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn should_parse_array_indexes() {
+        let path = KeyPath::parse("months[0]").unwrap();
+
+        assert_eq!(path, KeyPath::default().child("months").index(0));
+        assert_eq!(path.to_string(), "months[0]");
+        assert_eq!(
+            segments("list[1][2].title"),
+            vec!["list", "1", "2", "title"]
+        );
+    }
+
+    #[test]
+    fn should_sort_array_indexes_numerically() {
+        let mut paths = [
+            KeyPath::parse("months[10]").unwrap(),
+            KeyPath::parse("months[2]").unwrap(),
+        ];
+
+        paths.sort();
+
+        assert_eq!(paths[0].to_string(), "months[2]");
+    }
+
+    #[test]
+    fn should_display_path_with_escaped_dots() {
+        let path = KeyPath::default().child("errors").child("file.tooLarge");
+
+        assert_eq!(path.to_string(), "errors.file\\.tooLarge");
+        assert_eq!(path.to_dotted(), "errors.file.tooLarge");
+    }
+
+    #[test]
+    fn should_parse_displayed_path_back_to_same_path() {
+        let path = KeyPath::default()
+            .child("a.b")
+            .child("c[d]")
+            .index(2)
+            .child("e\\f")
+            .child("");
+
+        assert_eq!(KeyPath::parse(&path.to_string()).unwrap(), path);
+    }
+
+    #[test]
+    fn should_split_into_parent_and_key() {
+        let path = KeyPath::parse("foo.bar.baz").unwrap();
+        let (parent, key) = path.parent_and_key().unwrap();
+
+        assert_eq!(parent, vec!["foo", "bar"]);
+        assert_eq!(key, "baz");
+    }
+}
+
+#[cfg(test)]
+mod removal_order {
+    use super::*;
+
+    #[test]
+    fn should_put_later_array_elements_first() {
+        let mut paths: Vec<KeyPath> = ["a", "months[0]", "months[10].x", "months[2]", "months"]
+            .iter()
+            .map(|path| KeyPath::parse(path).unwrap())
+            .collect();
+
+        paths.sort_by(KeyPath::removal_order);
+
+        assert_eq!(
+            paths.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["a", "months", "months[10].x", "months[2]", "months[0]"]
+        );
+    }
+}
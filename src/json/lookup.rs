@@ -0,0 +1,138 @@
+use crate::json::key_path::KeyPath;
+use serde_json::Value;
+
+/// Returns object member or, for arrays, element at index given as text.
+pub fn get_child<'a>(value: &'a Value, segment: &str) -> Option<&'a Value> {
+    match value {
+        Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => value.get(segment),
+    }
+}
+
+pub fn get_child_mut<'a>(value: &'a mut Value, segment: &str) -> Option<&'a mut Value> {
+    match value {
+        Value::Array(items) => items.get_mut(segment.parse::<usize>().ok()?),
+        _ => value.get_mut(segment),
+    }
+}
+
+pub fn get_value_at_path<'a>(json: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    let mut current = json;
+
+    for key in path {
+        current = get_child(current, key)?;
+    }
+
+    Some(current)
+}
+
+/// Checks that a value can be inserted at path without overwriting anything.
+pub fn ensure_path_is_free(json: &Value, key_path: &KeyPath) -> Result<(), String> {
+    let path = key_path.segments();
+    let mut current = json;
+
+    for (i, key) in path.iter().enumerate() {
+        if !current.is_object() && !current.is_array() {
+            return Err(format!(
+                "Expected object at path segment '{}' of '{}', but found something else!",
+                path[i.saturating_sub(1)],
+                key_path
+            ));
+        }
+
+        match get_child(current, key) {
+            Some(value) => current = value,
+            None => return Ok(()),
+        }
+    }
+
+    Err(format!("Key '{}' already exists!", key_path))
+}
+
+#[cfg(test)]
+mod get_value_at_path {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_return_nested_value() {
+        let data = json!({
+            "foo": {
+                "bar": "value"
+            }
+        });
+
+        let result = get_value_at_path(&data, &["foo", "bar"]);
+
+        assert_eq!(result, Some(&json!("value")));
+    }
+
+    #[test]
+    fn should_return_array_element_by_index() {
+        let data = json!({
+            "months": ["January", "February"]
+        });
+
+        let result = get_value_at_path(&data, &["months", "1"]);
+
+        assert_eq!(result, Some(&json!("February")));
+    }
+
+    #[test]
+    fn should_return_none_if_path_does_not_exist() {
+        let data = json!({
+            "foo": "value"
+        });
+
+        let result = get_value_at_path(&data, &["foo", "bar"]);
+
+        assert_eq!(result, None);
+    }
+}
+
+#[cfg(test)]
+mod ensure_path_is_free {
+    use super::*;
+    use crate::test_support::key;
+    use serde_json::json;
+
+    #[test]
+    fn should_return_ok_if_path_does_not_exist() {
+        let data = json!({
+            "foo": {
+                "bar": "value"
+            }
+        });
+
+        let result = ensure_path_is_free(&data, &key("foo.baz.new"));
+
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_return_err_if_key_exists() {
+        let data = json!({
+            "foo": {
+                "bar": { "baz": "value" }
+            }
+        });
+
+        let result = ensure_path_is_free(&data, &key("foo.bar"));
+
+        assert_eq!(result.unwrap_err(), "Key 'foo.bar' already exists!");
+    }
+
+    #[test]
+    fn should_return_err_if_string_found_during_path_traversal() {
+        let data = json!({
+            "foo": "value"
+        });
+
+        let result = ensure_path_is_free(&data, &key("foo.bar.baz"));
+
+        assert_eq!(
+            result.unwrap_err(),
+            "Expected object at path segment 'foo' of 'foo.bar.baz', but found something else!"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::json::key_path::KeyPath;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Path changed differently on both sides. `None` means the key is missing on
+/// that side.
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+    pub path: KeyPath,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+/// Merges changes made in `ours` and `theirs` since `base`, key by key.
+/// Conflicting paths keep the value from `ours` and are returned alongside
+/// the result. `None` result means the value was removed.
+pub fn three_way_merge(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+) -> (Option<Value>, Vec<Conflict>) {
+    let mut conflicts = Vec::new();
+    let merged = merge_at_path(base, ours, theirs, KeyPath::default(), &mut conflicts);
+    (merged, conflicts)
+}
+
+fn merge_at_path(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    path: KeyPath,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<Value> {
+    if ours == theirs || base == theirs {
+        return ours.cloned();
+    }
+    if base == ours {
+        return theirs.cloned();
+    }
+
+    if let (Some(Value::Object(ours)), Some(Value::Object(theirs))) = (ours, theirs) {
+        let base = base.and_then(Value::as_object);
+        let keys: BTreeSet<&String> = base
+            .into_iter()
+            .flat_map(|base| base.keys())
+            .chain(ours.keys())
+            .chain(theirs.keys())
+            .collect();
+
+        let mut merged = Map::new();
+        for key in keys {
+            let value = merge_at_path(
+                base.and_then(|base| base.get(key)),
+                ours.get(key),
+                theirs.get(key),
+                path.child(key),
+                conflicts,
+            );
+            if let Some(value) = value {
+                merged.insert(key.clone(), value);
+            }
+        }
+        return Some(Value::Object(merged));
+    }
+
+    conflicts.push(Conflict {
+        path,
+        base: base.cloned(),
+        ours: ours.cloned(),
+        theirs: theirs.cloned(),
+    });
+    ours.cloned()
+}
+
+#[cfg(test)]
+mod three_way_merge {
+    use super::*;
+    use serde_json::json;
+
+    fn merge(base: Value, ours: Value, theirs: Value) -> (Option<Value>, Vec<Conflict>) {
+        three_way_merge(Some(&base), Some(&ours), Some(&theirs))
+    }
+
+    #[test]
+    fn should_take_changes_from_both_sides() {
+        let base = json!({ "a": "a", "b": "b", "c": "c" });
+        let ours = json!({ "a": "our_a", "b": "b", "c": "c", "d": "d" });
+        let theirs = json!({ "a": "a", "b": "their_b" });
+        let expected = json!({ "a": "our_a", "b": "their_b", "d": "d" });
+
+        let (result, conflicts) = merge(base, ours, theirs);
+
+        assert_eq!(result, Some(expected));
+        assert_eq!(conflicts, vec![]);
+    }
+
+    #[test]
+    fn should_merge_nested_objects() {
+        let base = json!({ "pax": { "rooms": "Rooms" } });
+        let ours = json!({ "pax": { "rooms": "Rooms", "adults": "Adults" } });
+        let theirs = json!({ "pax": { "rooms": "Rooms", "children": "Children" } });
+        let expected = json!({
+            "pax": { "rooms": "Rooms", "adults": "Adults", "children": "Children" }
+        });
+
+        let (result, conflicts) = merge(base, ours, theirs);
+
+        assert_eq!(result, Some(expected));
+        assert_eq!(conflicts, vec![]);
+    }
+
+    #[test]
+    fn should_accept_same_change_on_both_sides() {
+        let base = json!({ "a": "a" });
+        let ours = json!({ "a": "new_a" });
+        let theirs = json!({ "a": "new_a" });
+
+        let (result, conflicts) = merge(base, ours, theirs);
+
+        assert_eq!(result, Some(json!({ "a": "new_a" })));
+        assert_eq!(conflicts, vec![]);
+    }
+
+    #[test]
+    fn should_report_conflict_and_keep_ours() {
+        let base = json!({ "pax": { "rooms": "Rooms" } });
+        let ours = json!({ "pax": { "rooms": "Our rooms" } });
+        let theirs = json!({ "pax": { "rooms": "Their rooms" } });
+
+        let (result, conflicts) = merge(base, ours, theirs);
+
+        assert_eq!(result, Some(json!({ "pax": { "rooms": "Our rooms" } })));
+        assert_eq!(
+            conflicts,
+            vec![Conflict {
+                path: KeyPath::parse("pax.rooms").unwrap(),
+                base: Some(json!("Rooms")),
+                ours: Some(json!("Our rooms")),
+                theirs: Some(json!("Their rooms")),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_report_conflict_if_removed_key_was_changed_on_other_side() {
+        let base = json!({ "a": "a" });
+        let ours = json!({});
+        let theirs = json!({ "a": "new_a" });
+
+        let (result, conflicts) = merge(base, ours, theirs);
+
+        assert_eq!(result, Some(json!({})));
+        assert_eq!(conflicts[0].path, KeyPath::parse("a").unwrap());
+        assert_eq!(conflicts[0].ours, None);
+    }
+
+    #[test]
+    fn should_report_conflict_if_key_was_added_with_different_values() {
+        let (result, conflicts) =
+            three_way_merge(None, Some(&json!("ours")), Some(&json!("theirs")));
+
+        assert_eq!(result, Some(json!("ours")));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].base, None);
+    }
+}
@@ -1,6 +1,15 @@
 pub mod compare;
+pub mod copy;
+pub mod diff;
 pub mod insert;
+pub mod key_path;
+pub mod lookup;
+pub mod merge;
 pub mod paths;
 pub mod remove;
 pub mod rename;
 pub mod replace;
+pub mod schema;
+pub mod selector;
+pub mod shape;
+pub mod upsert;
@@ -1,32 +1,50 @@
+use crate::json::key_path::KeyPath;
 use serde_json::Value;
 
-pub fn get_json_paths(value: &Value) -> Result<Vec<String>, String> {
+/// Returns paths of every value in the file. Arrays are treated as single
+/// values, the way they are looked up in source code.
+pub fn get_json_paths(value: &Value) -> Vec<KeyPath> {
     let mut paths = Vec::new();
-    collect_paths(value, String::new(), &mut paths)?;
-    Ok(paths)
+    collect_paths(value, KeyPath::default(), &mut paths);
+    paths
 }
 
-fn collect_paths(value: &Value, prefix: String, paths: &mut Vec<String>) -> Result<(), String> {
+/// Returns paths of every key in the file, including keys holding objects
+/// and array elements.
+pub fn get_node_paths(value: &Value) -> Vec<KeyPath> {
+    let mut paths = Vec::new();
+    collect_node_paths(value, KeyPath::default(), &mut paths);
+    paths
+}
+
+fn collect_node_paths(value: &Value, prefix: KeyPath, paths: &mut Vec<KeyPath>) {
     match value {
         Value::Object(map) => {
             for (k, v) in map {
-                let new_prefix = if prefix.is_empty() {
-                    k.clone()
-                } else {
-                    format!("{}.{}", prefix, k)
-                };
-                collect_paths(v, new_prefix, paths)?;
+                let path = prefix.child(k);
+                paths.push(path.clone());
+                collect_node_paths(v, path, paths);
             }
-            Ok(())
         }
-        Value::String(_) => {
-            paths.push(prefix);
-            Ok(())
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                let path = prefix.index(i);
+                paths.push(path.clone());
+                collect_node_paths(v, path, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_paths(value: &Value, prefix: KeyPath, paths: &mut Vec<KeyPath>) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                collect_paths(v, prefix.child(k), paths);
+            }
         }
-        other => Err(format!(
-            "Unexpected value type at '{}': {:?}",
-            prefix, other
-        )),
+        _ => paths.push(prefix),
     }
 }
 #[cfg(test)]
@@ -36,17 +54,17 @@ mod get_json_paths {
 
     #[test]
     fn should_return_no_paths_for_empty_json_file() {
-        let mut data = json!({});
-        let expected: Vec<String> = vec![];
+        let data = json!({});
+        let expected: Vec<KeyPath> = vec![];
 
-        let result = get_json_paths(&mut data);
+        let result = get_json_paths(&data);
 
-        assert_eq!(result.unwrap(), expected);
+        assert_eq!(result, expected);
     }
 
     #[test]
     fn should_return_all_paths() {
-        let mut data = json!({
+        let data = json!({
             "foo": "value",
             "bar": {
                 "baz": "value",
@@ -58,14 +76,78 @@ mod get_json_paths {
                 }
             }
         });
-        let expected: Vec<String> = vec![
-            "bar.baz".to_owned(),
-            "bbb.ccc.ddd".to_owned(),
-            "foo".to_owned(),
+        let expected: Vec<KeyPath> = vec![
+            KeyPath::parse("bar.baz").unwrap(),
+            KeyPath::parse("bbb.ccc.ddd").unwrap(),
+            KeyPath::parse("foo").unwrap(),
+        ];
+
+        let result = get_json_paths(&data);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_return_paths_of_non_string_values() {
+        let data = json!({
+            "months": ["January", "February"],
+            "count": 12,
+            "enabled": false,
+            "empty": null
+        });
+        let expected: Vec<KeyPath> = vec![
+            KeyPath::parse("count").unwrap(),
+            KeyPath::parse("empty").unwrap(),
+            KeyPath::parse("enabled").unwrap(),
+            KeyPath::parse("months").unwrap(),
+        ];
+
+        let result = get_json_paths(&data);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_return_paths_with_dots_inside_keys() {
+        let data = json!({
+            "errors": {
+                "file.tooLarge": "value",
+            }
+        });
+        let expected: Vec<KeyPath> = vec![KeyPath::parse("errors.file\\.tooLarge").unwrap()];
+
+        let result = get_json_paths(&data);
+
+        assert_eq!(result, expected);
+    }
+}
+
+#[cfg(test)]
+mod get_node_paths {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_return_paths_of_objects_and_values() {
+        let data = json!({
+            "foo": "value",
+            "bar": {
+                "baz": "value",
+            },
+            "aaa": {},
+            "list": ["a"]
+        });
+        let expected: Vec<KeyPath> = vec![
+            KeyPath::parse("aaa").unwrap(),
+            KeyPath::parse("bar").unwrap(),
+            KeyPath::parse("bar.baz").unwrap(),
+            KeyPath::parse("foo").unwrap(),
+            KeyPath::parse("list").unwrap(),
+            KeyPath::parse("list[0]").unwrap(),
         ];
 
-        let result = get_json_paths(&mut data);
+        let result = get_node_paths(&data);
 
-        assert_eq!(result.unwrap(), expected);
+        assert_eq!(result, expected);
     }
 }
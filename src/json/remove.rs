@@ -1,3 +1,4 @@
+use crate::json::lookup::get_child_mut;
 use serde_json::Value;
 
 pub fn remove_key_at_path(
@@ -8,7 +9,7 @@ pub fn remove_key_at_path(
     let mut current = json;
 
     for segment in path {
-        current = current.get_mut(*segment).ok_or_else(|| {
+        current = get_child_mut(current, segment).ok_or_else(|| {
             format!(
                 "Path segment '{}' does not exist in the JSON structure!",
                 segment
@@ -21,6 +22,18 @@ pub fn remove_key_at_path(
             obj.remove(key_to_remove);
             Ok(())
         }
+        Value::Array(items) => {
+            let index: usize = key_to_remove.parse().map_err(|_| {
+                format!(
+                    "Expected index to remove from array, found '{}'!",
+                    key_to_remove
+                )
+            })?;
+            if index < items.len() {
+                items.remove(index);
+            }
+            Ok(())
+        }
         _ => Err("Expected object at final path, but found non-object.".to_string()),
     }
 }
@@ -159,4 +172,19 @@ mod remove_key_at_path {
         assert_eq!(data, expected);
         assert_eq!(result.unwrap(), ());
     }
+
+    #[test]
+    fn should_remove_array_element_at_index() {
+        let mut data = json!({
+            "months": ["January", "February", "March"]
+        });
+        let expected = json!({
+            "months": ["January", "March"]
+        });
+
+        let result = remove_key_at_path(&mut data, &["months"], "1");
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
 }
@@ -1,22 +1,64 @@
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_child_mut;
 use serde_json::Value;
+use std::fmt;
+
+/// What to do when the destination key of a rename already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    Fail,
+    Overwrite,
+    Merge,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RenameError {
+    NotFound(KeyPath),
+    DestinationExists(KeyPath),
+    NotAnObject(String),
+    MergeConflicts(Vec<KeyPath>),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::NotFound(path) => write!(f, "Key '{}' not found during rename!", path),
+            RenameError::DestinationExists(path) => write!(
+                f,
+                "Key '{}' already exists! Use --overwrite or --merge.",
+                path
+            ),
+            RenameError::NotAnObject(segment) => write!(
+                f,
+                "Expected object at path segment '{}', but found something else!",
+                segment
+            ),
+            RenameError::MergeConflicts(paths) => {
+                let paths: Vec<String> = paths.iter().map(ToString::to_string).collect();
+                write!(f, "Can't merge, values differ at '{}'!", paths.join("', '"))
+            }
+        }
+    }
+}
 
 pub fn rename_key_at_path(
     json: &mut Value,
-    from_path: &[&str],
-    to_path: &[&str],
-) -> Result<(), String> {
-    if let Some(value) = take_value_at_path(json, from_path) {
-        insert_value_at_path(json, to_path, value);
-        Ok(())
-    } else {
-        Err(format!(
-            "Key '{}' not found during rename!",
-            from_path.join(".")
-        ))
+    from_path: &KeyPath,
+    to_path: &KeyPath,
+    strategy: ConflictStrategy,
+) -> Result<(), RenameError> {
+    let value = take_value_at_path(json, &from_path.segments())
+        .ok_or_else(|| RenameError::NotFound(from_path.clone()))?;
+
+    if let Err((error, value)) = place_value_at_path(json, to_path, value, strategy) {
+        restore_value_at_path(json, &from_path.segments(), value);
+        return Err(error);
     }
+
+    Ok(())
 }
 
-fn take_value_at_path<'a>(json: &'a mut Value, path: &[&str]) -> Option<Value> {
+pub fn take_value_at_path(json: &mut Value, path: &[&str]) -> Option<Value> {
     if path.is_empty() {
         return None;
     }
@@ -24,33 +66,220 @@ fn take_value_at_path<'a>(json: &'a mut Value, path: &[&str]) -> Option<Value> {
     let mut current = json;
 
     for key in &path[..path.len() - 1] {
-        current = current.get_mut(*key)?;
+        current = get_child_mut(current, key)?;
     }
 
-    current.as_object_mut()?.remove(path[path.len() - 1])
+    let key = path[path.len() - 1];
+    match current {
+        Value::Array(items) => {
+            let index: usize = key.parse().ok()?;
+            (index < items.len()).then(|| items.remove(index))
+        }
+        _ => current.as_object_mut()?.remove(key),
+    }
 }
 
-fn insert_value_at_path(json: &mut Value, path: &[&str], value: Value) {
+/// Puts value taken by `take_value_at_path` back. Array elements after it were
+/// shifted by the removal, so the value is inserted, not assigned.
+fn restore_value_at_path(json: &mut Value, path: &[&str], value: Value) {
+    let Some((key, parent)) = path.split_last() else {
+        return;
+    };
     let mut current = json;
+    for segment in parent {
+        match get_child_mut(current, segment) {
+            Some(child) => current = child,
+            None => return,
+        }
+    }
 
-    for key in &path[..path.len() - 1] {
-        current = current
-            .as_object_mut()
-            .unwrap()
-            .entry(*key)
-            .or_insert_with(|| Value::Object(Default::default()));
+    match current {
+        Value::Array(items) => {
+            if let Ok(index) = key.parse::<usize>() {
+                items.insert(index.min(items.len()), value);
+            }
+        }
+        Value::Object(obj) => {
+            obj.insert(key.to_string(), value);
+        }
+        _ => {}
+    }
+}
+
+/// Inserts value at path, creating missing objects on the way. Existing value
+/// at path is replaced. Index equal to array length appends to the array.
+pub fn insert_value_at_path(
+    json: &mut Value,
+    path: &[&str],
+    value: Value,
+) -> Result<(), RenameError> {
+    let (key, parent) = path
+        .split_last()
+        .ok_or_else(|| RenameError::NotAnObject(String::new()))?;
+    let mut current = json;
+
+    for segment in parent {
+        current = if current.is_array() {
+            get_child_mut(current, segment)
+                .ok_or_else(|| RenameError::NotAnObject(segment.to_string()))?
+        } else {
+            current
+                .as_object_mut()
+                .ok_or_else(|| RenameError::NotAnObject(segment.to_string()))?
+                .entry(*segment)
+                .or_insert_with(|| Value::Object(Default::default()))
+        };
+    }
+
+    match current {
+        Value::Object(obj) => {
+            obj.insert(key.to_string(), value);
+            Ok(())
+        }
+        Value::Array(items) => match key.parse::<usize>() {
+            Ok(index) if index < items.len() => {
+                items[index] = value;
+                Ok(())
+            }
+            Ok(index) if index == items.len() => {
+                items.push(value);
+                Ok(())
+            }
+            _ => Err(RenameError::NotAnObject(key.to_string())),
+        },
+        _ => Err(RenameError::NotAnObject(key.to_string())),
+    }
+}
+
+/// Places value at path according to strategy. On failure the value is handed
+/// back together with the error and json is left untouched.
+fn place_value_at_path(
+    json: &mut Value,
+    key_path: &KeyPath,
+    value: Value,
+    strategy: ConflictStrategy,
+) -> Result<(), (RenameError, Value)> {
+    let path = key_path.segments();
+    if path.is_empty() {
+        return Err((RenameError::DestinationExists(KeyPath::default()), value));
+    }
+
+    let existing = match find_existing(json, &path) {
+        Ok(existing) => existing,
+        Err(error) => return Err((error, value)),
+    };
+
+    let Some(existing) = existing else {
+        // `find_existing` already checked objects on the way, so insert can't fail.
+        insert_value_at_path(json, &path, value).ok();
+        return Ok(());
+    };
+
+    match strategy {
+        ConflictStrategy::Fail => Err((RenameError::DestinationExists(key_path.clone()), value)),
+        ConflictStrategy::Overwrite => {
+            *existing = value;
+            Ok(())
+        }
+        ConflictStrategy::Merge => {
+            let mut conflicts = Vec::new();
+            find_merge_conflicts(existing, &value, key_path.clone(), &mut conflicts);
+            if !conflicts.is_empty() {
+                return Err((RenameError::MergeConflicts(conflicts), value));
+            }
+            merge_values(existing, value);
+            Ok(())
+        }
+    }
+}
+
+/// Returns value at path if it exists, or `None` when path is free.
+fn find_existing<'a>(
+    json: &'a mut Value,
+    path: &[&str],
+) -> Result<Option<&'a mut Value>, RenameError> {
+    let mut current = json;
+
+    for (i, segment) in path.iter().enumerate() {
+        if let Value::Array(items) = current {
+            let is_next_index = segment.parse() == Ok(items.len());
+            if is_next_index && i == path.len() - 1 {
+                return Ok(None);
+            }
+        } else if !current.is_object() {
+            return Err(RenameError::NotAnObject(segment.to_string()));
+        }
+
+        let is_object = current.is_object();
+        match get_child_mut(current, segment) {
+            Some(value) => current = value,
+            None if is_object => return Ok(None),
+            None => return Err(RenameError::NotAnObject(segment.to_string())),
+        }
+    }
+
+    Ok(Some(current))
+}
+
+fn find_merge_conflicts(
+    existing: &Value,
+    incoming: &Value,
+    path: KeyPath,
+    conflicts: &mut Vec<KeyPath>,
+) {
+    match (existing, incoming) {
+        (Value::Object(existing), Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                if let Some(current) = existing.get(key) {
+                    find_merge_conflicts(current, value, path.child(key), conflicts);
+                }
+            }
+        }
+        (existing, incoming) if existing == incoming => {}
+        _ => conflicts.push(path),
     }
+}
 
-    if let Some(obj) = current.as_object_mut() {
-        obj.insert(path[path.len() - 1].to_string(), value);
+fn merge_values(existing: &mut Value, incoming: Value) {
+    match (existing, incoming) {
+        (Value::Object(existing), Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                match existing.get_mut(&key) {
+                    Some(current) => merge_values(current, value),
+                    None => {
+                        existing.insert(key, value);
+                    }
+                }
+            }
+        }
+        (existing, incoming) => *existing = incoming,
     }
 }
 
 #[cfg(test)]
 mod rename_key_at_path {
     use super::*;
+    use crate::test_support::key;
     use serde_json::json;
 
+    #[test]
+    fn should_put_array_element_back_in_place_on_failure() {
+        let mut data = json!({ "months": ["Jan", "Feb", "Mar"], "x": "taken" });
+
+        let result = rename_key_at_path(
+            &mut data,
+            &key("months[0]"),
+            &key("x"),
+            ConflictStrategy::Fail,
+        );
+
+        assert_eq!(result, Err(RenameError::DestinationExists(key("x"))));
+        assert_eq!(
+            data,
+            json!({ "months": ["Jan", "Feb", "Mar"], "x": "taken" })
+        );
+    }
+
     #[test]
     fn should_rename_key_at_path() {
         let mut data = json!({
@@ -60,7 +289,12 @@ mod rename_key_at_path {
             "new_key": "value"
         });
 
-        let result = rename_key_at_path(&mut data, &["key"], &["new_key"]);
+        let result = rename_key_at_path(
+            &mut data,
+            &key("key"),
+            &key("new_key"),
+            ConflictStrategy::Fail,
+        );
 
         assert_eq!(data, expected);
         assert_eq!(result.unwrap(), ());
@@ -79,7 +313,12 @@ mod rename_key_at_path {
             }
         });
 
-        let result = rename_key_at_path(&mut data, &["foo", "key"], &["foo", "new_key"]);
+        let result = rename_key_at_path(
+            &mut data,
+            &key("foo.key"),
+            &key("foo.new_key"),
+            ConflictStrategy::Fail,
+        );
 
         assert_eq!(data, expected);
         assert_eq!(result.unwrap(), ());
@@ -104,8 +343,9 @@ mod rename_key_at_path {
 
         let result = rename_key_at_path(
             &mut data,
-            &["foo", "bar", "key"],
-            &["foo", "bar", "new_key"],
+            &key("foo.bar.key"),
+            &key("foo.bar.new_key"),
+            ConflictStrategy::Fail,
         );
 
         assert_eq!(data, expected);
@@ -113,7 +353,7 @@ mod rename_key_at_path {
     }
 
     #[test]
-    fn should_move_existing_key_to_parent_key() {
+    fn should_move_existing_key_to_parent_key_with_overwrite() {
         let mut data = json!({
             "foo": {
                 "bar": {
@@ -128,7 +368,12 @@ mod rename_key_at_path {
             }
         });
 
-        let result = rename_key_at_path(&mut data, &["foo", "bar", "key"], &["foo", "bar"]);
+        let result = rename_key_at_path(
+            &mut data,
+            &key("foo.bar.key"),
+            &key("foo.bar"),
+            ConflictStrategy::Overwrite,
+        );
 
         assert_eq!(data, expected);
         assert_eq!(result.unwrap(), ());
@@ -150,7 +395,12 @@ mod rename_key_at_path {
             }
         });
 
-        let result = rename_key_at_path(&mut data, &["foo", "bar", "key"], &["foo", "baz"]);
+        let result = rename_key_at_path(
+            &mut data,
+            &key("foo.bar.key"),
+            &key("foo.baz"),
+            ConflictStrategy::Fail,
+        );
 
         assert_eq!(data, expected);
         assert_eq!(result.unwrap(), ());
@@ -173,12 +423,153 @@ mod rename_key_at_path {
             }
         });
 
-        let result = rename_key_at_path(&mut data, &["foo", "bar", "other_key"], &["foo", "baz"]);
+        let result = rename_key_at_path(
+            &mut data,
+            &key("foo.bar.other_key"),
+            &key("foo.baz"),
+            ConflictStrategy::Fail,
+        );
 
         assert_eq!(data, expected);
         assert_eq!(
             result.unwrap_err(),
-            "Key 'foo.bar.other_key' not found during rename!"
+            RenameError::NotFound(key("foo.bar.other_key"))
+        );
+    }
+
+    #[test]
+    fn should_return_err_and_keep_file_intact_if_destination_exists() {
+        let mut data = json!({
+            "foo": {
+                "bar": {
+                    "key": "value",
+                    "other_key": "value"
+                }
+            }
+        });
+        let expected = data.clone();
+
+        let result = rename_key_at_path(
+            &mut data,
+            &key("foo.bar.key"),
+            &key("foo.bar"),
+            ConflictStrategy::Fail,
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(
+            result.unwrap_err(),
+            RenameError::DestinationExists(key("foo.bar"))
+        );
+    }
+
+    #[test]
+    fn should_return_err_instead_of_panicking_if_string_found_during_path_traversal() {
+        let mut data = json!({
+            "foo": "value",
+            "key": "value"
+        });
+        let expected = data.clone();
+
+        let result = rename_key_at_path(
+            &mut data,
+            &key("key"),
+            &key("foo.bar.key"),
+            ConflictStrategy::Overwrite,
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(
+            result.unwrap_err(),
+            RenameError::NotAnObject("bar".to_owned())
+        );
+    }
+
+    #[test]
+    fn should_move_array_element_to_object_key() {
+        let mut data = json!({
+            "months": ["January", "February"]
+        });
+        let expected = json!({
+            "months": ["January"],
+            "last_month": "February"
+        });
+
+        let result = rename_key_at_path(
+            &mut data,
+            &key("months[1]"),
+            &key("last_month"),
+            ConflictStrategy::Fail,
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_merge_object_into_existing_object() {
+        let mut data = json!({
+            "old": {
+                "a": "value_a",
+                "b": "value_b"
+            },
+            "new": {
+                "b": "value_b",
+                "c": "value_c"
+            }
+        });
+        let expected = json!({
+            "new": {
+                "a": "value_a",
+                "b": "value_b",
+                "c": "value_c"
+            }
+        });
+
+        let result =
+            rename_key_at_path(&mut data, &key("old"), &key("new"), ConflictStrategy::Merge);
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_return_err_and_keep_file_intact_if_merged_values_differ() {
+        let mut data = json!({
+            "old": {
+                "a": "value_a",
+                "b": "old_value_b"
+            },
+            "new": {
+                "b": "new_value_b"
+            }
+        });
+        let expected = data.clone();
+
+        let result =
+            rename_key_at_path(&mut data, &key("old"), &key("new"), ConflictStrategy::Merge);
+
+        assert_eq!(data, expected);
+        assert_eq!(
+            result.unwrap_err(),
+            RenameError::MergeConflicts(vec![key("new.b")])
+        );
+    }
+
+    #[test]
+    fn should_name_key_containing_dot_unambiguously() {
+        let mut data = json!({ "errors": { "file": "File" } });
+
+        let result = rename_key_at_path(
+            &mut data,
+            &key("errors[\"file.tooLarge\"]"),
+            &key("errors.large"),
+            ConflictStrategy::Fail,
+        );
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Key 'errors.file\\.tooLarge' not found during rename!"
         );
     }
 }
@@ -1,22 +1,23 @@
+use crate::json::lookup::get_child_mut;
 use serde_json::Value;
 
 pub fn replace_value_at_key(
     json: &mut Value,
     path: &[&str],
     key: &str,
-    new_value: &str,
+    new_value: impl Into<Value>,
 ) -> Result<(), String> {
     let mut current = json;
 
     for segment in path {
-        current = current.get_mut(*segment).ok_or_else(|| {
+        current = get_child_mut(current, segment).ok_or_else(|| {
             format!(
                 "Expected path segment '{}' to exist, but it was not found.",
                 segment
             )
         })?;
 
-        if !current.is_object() {
+        if !current.is_object() && !current.is_array() {
             return Err(format!(
                 "Expected object at path segment '{}', but found non-object.",
                 segment
@@ -24,22 +25,22 @@ pub fn replace_value_at_key(
         }
     }
 
-    match current.as_object_mut() {
-        Some(obj) => {
-            if obj.contains_key(key) {
-                obj.insert(key.to_string(), Value::String(new_value.to_string()));
-                Ok(())
-            } else {
-                Err(format!(
-                    "Key '{}' does not exist at the target path! Use ADD command instead.",
-                    key
-                ))
-            }
-        }
-        None => Err(
+    if !current.is_object() && !current.is_array() {
+        return Err(
             "Expected an object at final path to replace value in, but found non-object."
                 .to_string(),
-        ),
+        );
+    }
+
+    match get_child_mut(current, key) {
+        Some(value) => {
+            *value = new_value.into();
+            Ok(())
+        }
+        None => Err(format!(
+            "Key '{}' does not exist at the target path! Use ADD command instead.",
+            key
+        )),
     }
 }
 
@@ -85,4 +86,26 @@ mod replace_value_at_key {
         assert_eq!(data, expected);
         assert_eq!(result.unwrap(), ());
     }
+
+    #[test]
+    fn should_replace_array_element_at_index() {
+        let mut data = json!({ "months": ["Jan", "Feb"] });
+        let expected = json!({ "months": ["Jan", "February"] });
+
+        let result = replace_value_at_key(&mut data, &["months"], "1", "February");
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
+
+    #[test]
+    fn should_replace_string_with_array() {
+        let mut data = json!({ "key": "old_value" });
+        let expected = json!({ "key": ["a", "b"] });
+
+        let result = replace_value_at_key(&mut data, &[], "key", json!(["a", "b"]));
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), ());
+    }
 }
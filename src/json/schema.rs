@@ -0,0 +1,162 @@
+use crate::json::key_path::KeyPath;
+use crate::json::shape::type_name;
+use serde_json::{Map, Value, json};
+
+const DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Generates JSON Schema of reference locale. Every object requires all its
+/// keys and allows no other, every value has to keep its type.
+pub fn generate_schema(reference: &Value) -> Value {
+    let mut schema = schema_of(reference);
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("$schema".to_string(), json!(DRAFT));
+    }
+    schema
+}
+
+fn schema_of(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let properties: Map<String, Value> = map
+                .iter()
+                .map(|(key, value)| (key.clone(), schema_of(value)))
+                .collect();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": map.keys().collect::<Vec<_>>(),
+                "additionalProperties": false,
+            })
+        }
+        value => json!({ "type": type_name(value) }),
+    }
+}
+
+/// Validates JSON against schema made by `generate_schema`. Only keywords
+/// used there are supported: `type`, `properties`, `required` and
+/// `additionalProperties`. Returns path and message of every problem.
+pub fn validate_against_schema(schema: &Value, json: &Value) -> Vec<(KeyPath, String)> {
+    let mut problems = Vec::new();
+    validate_value(schema, json, KeyPath::default(), &mut problems);
+    problems
+}
+
+fn validate_value(
+    schema: &Value,
+    value: &Value,
+    path: KeyPath,
+    problems: &mut Vec<(KeyPath, String)>,
+) {
+    let expected: Vec<&str> = match schema.get("type") {
+        Some(Value::String(expected)) => vec![expected.as_str()],
+        Some(Value::Array(expected)) => expected.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    let found = type_name(value);
+    let matches = |expected: &&str| {
+        *expected == found || (*expected == "integer" && (value.is_i64() || value.is_u64()))
+    };
+    if !expected.is_empty() && !expected.iter().any(matches) {
+        problems.push((
+            path,
+            format!("expected {}, found {}", expected.join(" or "), found),
+        ));
+        return;
+    }
+
+    let Value::Object(map) = value else {
+        return;
+    };
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !map.contains_key(key) {
+                problems.push((path.child(key), "missing".to_string()));
+            }
+        }
+    }
+
+    for (key, value) in map {
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(schema) => validate_value(schema, value, path.child(key), problems),
+            None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                problems.push((path.child(key), "not in schema".to_string()))
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod generate_schema {
+    use super::*;
+
+    #[test]
+    fn should_require_every_key_and_keep_types() {
+        let reference = json!({ "pax": { "rooms": "Rooms" }, "items": ["a"] });
+
+        let result = generate_schema(&reference);
+
+        assert_eq!(
+            result,
+            json!({
+                "$schema": DRAFT,
+                "type": "object",
+                "properties": {
+                    "items": { "type": "array" },
+                    "pax": {
+                        "type": "object",
+                        "properties": { "rooms": { "type": "string" } },
+                        "required": ["rooms"],
+                        "additionalProperties": false,
+                    },
+                },
+                "required": ["items", "pax"],
+                "additionalProperties": false,
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod validate_against_schema {
+    use super::*;
+
+    fn messages(problems: Vec<(KeyPath, String)>) -> Vec<String> {
+        problems
+            .into_iter()
+            .map(|(path, message)| format!("{}: {}", path, message))
+            .collect()
+    }
+
+    #[test]
+    fn should_accept_locale_matching_reference() {
+        let schema = generate_schema(&json!({ "pax": { "rooms": "Rooms" } }));
+
+        let result = validate_against_schema(&schema, &json!({ "pax": { "rooms": "Rum" } }));
+
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn should_report_missing_and_extra_keys() {
+        let schema = generate_schema(&json!({ "pax": { "rooms": "Rooms" } }));
+
+        let result = validate_against_schema(&schema, &json!({ "pax": { "adults": "Vuxna" } }));
+
+        assert_eq!(
+            messages(result),
+            vec!["pax.rooms: missing", "pax.adults: not in schema"]
+        );
+    }
+
+    #[test]
+    fn should_report_type_mismatch_once() {
+        let schema = generate_schema(&json!({ "pax": { "rooms": "Rooms" } }));
+
+        let result = validate_against_schema(&schema, &json!({ "pax": "Rum" }));
+
+        assert_eq!(messages(result), vec!["pax: expected object, found string"]);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::json::key_path::{KeyPath, escape_segment};
+use regex::Regex;
+use std::collections::BTreeSet;
+
+// One segment of a displayed key path: anything but an unescaped dot.
+const SEGMENT: &str = r"(?:[^.\\]|\\.)";
+
+/// Selects keys to operate on. Supported forms:
+/// - exact key path (`pax.rooms`),
+/// - glob, where `*` matches within a single segment and `**` matches any
+///   number of segments (`checkout.legacy.*`, `**.tooltip`),
+/// - regex matched against the whole key path (`re:^pax\.(adults|children)$`).
+#[derive(Debug)]
+pub enum Selector {
+    Exact(KeyPath),
+    Pattern(Regex),
+}
+
+impl Selector {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        if let Some(pattern) = input.strip_prefix("re:") {
+            let regex = Regex::new(pattern)
+                .map_err(|e| format!("Invalid regex selector '{}': {}", pattern, e))?;
+            return Ok(Selector::Pattern(regex));
+        }
+
+        let path = KeyPath::parse(input)?;
+
+        if path.segments().iter().any(|segment| segment.contains('*')) {
+            Ok(Selector::Pattern(glob_to_regex(&path)?))
+        } else {
+            Ok(Selector::Exact(path))
+        }
+    }
+
+    /// Returns paths matching the selector. When both a key and its
+    /// descendants match, only the outermost key is returned.
+    pub fn select(&self, paths: &BTreeSet<KeyPath>) -> Vec<KeyPath> {
+        let mut selected: Vec<KeyPath> = Vec::new();
+
+        for path in paths {
+            let matches = match self {
+                Selector::Exact(exact) => path == exact,
+                Selector::Pattern(regex) => regex.is_match(&path.to_string()),
+            };
+
+            if matches && !selected.iter().any(|parent| path.starts_with(parent)) {
+                selected.push(path.clone());
+            }
+        }
+
+        selected
+    }
+
+    /// Builds destination path for a selected path. For patterns, `$1`, `${1}`
+    /// or `${name}` in template are replaced with captured parts of the path.
+    pub fn destination(&self, path: &KeyPath, template: &str) -> Result<KeyPath, String> {
+        match self {
+            Selector::Exact(_) => KeyPath::parse(template),
+            Selector::Pattern(regex) => {
+                let path_str = path.to_string();
+                let captures = regex
+                    .captures(&path_str)
+                    .ok_or_else(|| format!("Key '{}' does not match selector!", path_str))?;
+                let mut destination = String::new();
+                captures.expand(template, &mut destination);
+                KeyPath::parse(&destination)
+            }
+        }
+    }
+}
+
+fn glob_to_regex(path: &KeyPath) -> Result<Regex, String> {
+    let segments = path.segments();
+    let last = segments.len() - 1;
+    let mut pattern = String::from("^");
+
+    for (i, segment) in segments.iter().enumerate() {
+        if *segment == "**" {
+            let any_segments = format!("({0}+(?:\\.{0}+)*)", SEGMENT);
+            if i == last {
+                pattern.push_str(&any_segments);
+            } else {
+                pattern.push_str(&format!("(?:{}\\.)?", any_segments));
+            }
+            continue;
+        }
+
+        let parts: Vec<String> = segment
+            .split('*')
+            .map(|part| regex::escape(&escape_segment(part)))
+            .collect();
+        pattern.push_str(&parts.join(&format!("({}*)", SEGMENT)));
+
+        if i != last {
+            pattern.push_str("\\.");
+        }
+    }
+
+    pattern.push('$');
+
+    Regex::new(&pattern).map_err(|e| format!("Invalid glob selector '{}': {}", path, e))
+}
+
+#[cfg(test)]
+mod select {
+    use super::*;
+
+    fn paths(input: &[&str]) -> BTreeSet<KeyPath> {
+        input.iter().map(|p| KeyPath::parse(p).unwrap()).collect()
+    }
+
+    fn select(selector: &str, input: &[&str]) -> Vec<String> {
+        Selector::parse(selector)
+            .unwrap()
+            .select(&paths(input))
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn should_select_exact_path() {
+        let result = select("pax.rooms", &["pax", "pax.rooms", "pax.adults"]);
+
+        assert_eq!(result, vec!["pax.rooms"]);
+    }
+
+    #[test]
+    fn should_select_nothing_if_exact_path_does_not_exist() {
+        let result = select("pax.other", &["pax", "pax.rooms"]);
+
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    #[test]
+    fn should_select_single_level_with_star() {
+        let result = select(
+            "checkout.legacy.*",
+            &[
+                "checkout",
+                "checkout.legacy",
+                "checkout.legacy.a",
+                "checkout.legacy.b",
+                "checkout.legacy.b.c",
+                "checkout.other",
+            ],
+        );
+
+        assert_eq!(result, vec!["checkout.legacy.a", "checkout.legacy.b"]);
+    }
+
+    #[test]
+    fn should_select_star_within_segment() {
+        let result = select("pax.add_*", &["pax.add_room", "pax.add_child", "pax.rooms"]);
+
+        assert_eq!(result, vec!["pax.add_child", "pax.add_room"]);
+    }
+
+    #[test]
+    fn should_select_any_depth_with_double_star() {
+        let result = select(
+            "**.tooltip",
+            &[
+                "tooltip",
+                "a",
+                "a.tooltip",
+                "a.b",
+                "a.b.tooltip",
+                "a.b.title",
+            ],
+        );
+
+        assert_eq!(result, vec!["a.b.tooltip", "a.tooltip", "tooltip"]);
+    }
+
+    #[test]
+    fn should_not_match_dots_inside_keys_with_star() {
+        let result = select("errors.*", &["errors.file\\.tooLarge", "errors.file"]);
+
+        assert_eq!(result, vec!["errors.file", "errors.file\\.tooLarge"]);
+    }
+
+    #[test]
+    fn should_select_with_regex() {
+        let result = select(
+            "re:^pax\\.(adults|children)$",
+            &["pax.adults", "pax.children", "pax.rooms"],
+        );
+
+        assert_eq!(result, vec!["pax.adults", "pax.children"]);
+    }
+}
+
+#[cfg(test)]
+mod destination {
+    use super::*;
+
+    fn destination(selector: &str, path: &str, template: &str) -> Result<String, String> {
+        Selector::parse(selector)
+            .unwrap()
+            .destination(&KeyPath::parse(path).unwrap(), template)
+            .map(|p| p.to_string())
+    }
+
+    #[test]
+    fn should_use_template_as_is_for_exact_selector() {
+        let result = destination("pax.rooms", "pax.room", "booking.rooms");
+
+        assert_eq!(result.unwrap(), "booking.rooms");
+    }
+
+    #[test]
+    fn should_substitute_glob_captures() {
+        let result = destination("pax.*", "pax.rooms", "booking.pax.$1");
+
+        assert_eq!(result.unwrap(), "booking.pax.rooms");
+    }
+
+    #[test]
+    fn should_substitute_double_star_captures() {
+        let result = destination("**.tooltip", "a.b.tooltip", "${1}.hint");
+
+        assert_eq!(result.unwrap(), "a.b.hint");
+    }
+
+    #[test]
+    fn should_substitute_regex_captures() {
+        let result = destination("re:^pax\\.(adults|children)$", "pax.adults", "people.$1");
+
+        assert_eq!(result.unwrap(), "people.adults");
+    }
+
+    #[test]
+    fn should_return_err_if_substitution_produces_invalid_path() {
+        let result = destination("**.tooltip", "tooltip", "${1}.hint");
+
+        assert_eq!(
+            result.unwrap_err(),
+            "Invalid key path '.hint': empty segment!"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::{get_child, get_child_mut, get_value_at_path};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Value of different type in target than in reference, e.g. a string where
+/// reference has an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeConflict {
+    pub path: KeyPath,
+    pub reference: &'static str,
+    pub target: &'static str,
+}
+
+impl fmt::Display for ShapeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} in reference, {} in target)",
+            self.path, self.reference, self.target
+        )
+    }
+}
+
+/// Returns conflicts of paths present in both files. Values under a
+/// conflicting path aren't compared any further.
+pub fn find_shape_conflicts(reference: &Value, target: &Value) -> Vec<ShapeConflict> {
+    let mut conflicts = Vec::new();
+    collect_conflicts(reference, target, KeyPath::default(), &mut conflicts);
+    conflicts
+}
+
+fn collect_conflicts(
+    reference: &Value,
+    target: &Value,
+    path: KeyPath,
+    conflicts: &mut Vec<ShapeConflict>,
+) {
+    match (reference, target) {
+        (Value::Object(reference_map), Value::Object(_)) => {
+            for (key, reference_value) in reference_map {
+                if let Some(target_value) = get_child(target, key) {
+                    collect_conflicts(reference_value, target_value, path.child(key), conflicts);
+                }
+            }
+        }
+        (Value::Array(reference_items), Value::Array(target_items)) => {
+            for (i, (reference_value, target_value)) in
+                reference_items.iter().zip(target_items).enumerate()
+            {
+                collect_conflicts(reference_value, target_value, path.index(i), conflicts);
+            }
+        }
+        _ if type_name(reference) != type_name(target) => conflicts.push(ShapeConflict {
+            path,
+            reference: type_name(reference),
+            target: type_name(target),
+        }),
+        _ => {}
+    }
+}
+
+/// Moves every value conflicting with reference aside, to a sibling key with
+/// `_old` suffix. Where reference has an object or array, an empty one takes
+/// its place, so missing keys can be added under it. Returns old and new path
+/// of every moved value.
+pub fn fix_shape_conflicts(
+    reference: &Value,
+    target: &mut Value,
+) -> Result<Vec<(KeyPath, KeyPath)>, String> {
+    let mut moved = Vec::new();
+
+    for conflict in find_shape_conflicts(reference, target) {
+        let (parent, key) = conflict.path.parent_and_key()?;
+        let mut current = &mut *target;
+        for segment in &parent {
+            current = get_child_mut(current, segment)
+                .ok_or_else(|| format!("Key '{}' not found!", conflict.path))?;
+        }
+        let Value::Object(map) = current else {
+            return Err(format!(
+                "Can't move '{}' aside, it's an array element!",
+                conflict.path
+            ));
+        };
+
+        let aside = (1..)
+            .map(|n| match n {
+                1 => format!("{}_old", key),
+                n => format!("{}_old{}", key, n),
+            })
+            .find(|name| !map.contains_key(name))
+            .unwrap();
+        let value = map.remove(key).unwrap_or(Value::Null);
+        map.insert(aside.clone(), value);
+
+        match get_value_at_path(reference, &conflict.path.segments()) {
+            Some(Value::Object(_)) => {
+                map.insert(key.to_string(), Value::Object(Map::new()));
+            }
+            Some(Value::Array(_)) => {
+                map.insert(key.to_string(), Value::Array(Vec::new()));
+            }
+            _ => {}
+        }
+
+        let aside_path = conflict.path.parent().child(&aside);
+        moved.push((conflict.path, aside_path));
+    }
+
+    Ok(moved)
+}
+
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod find_shape_conflicts {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_report_object_replaced_by_text() {
+        let reference = json!({ "pax": { "rooms": "Rooms" }, "title": "Title" });
+        let target = json!({ "pax": "Resenärer", "title": "Titel" });
+
+        let result = find_shape_conflicts(&reference, &target);
+
+        assert_eq!(
+            result,
+            vec![ShapeConflict {
+                path: KeyPath::parse("pax").unwrap(),
+                reference: "object",
+                target: "string",
+            }]
+        );
+    }
+
+    #[test]
+    fn should_report_text_replaced_by_array() {
+        let reference = json!({ "days": "Mon" });
+        let target = json!({ "days": ["Mån"] });
+
+        let result = find_shape_conflicts(&reference, &target);
+
+        assert_eq!(
+            result[0].to_string(),
+            "days (string in reference, array in target)"
+        );
+    }
+}
+
+#[cfg(test)]
+mod fix_shape_conflicts {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_move_conflicting_value_aside() {
+        let reference = json!({ "pax": { "rooms": "Rooms" } });
+        let mut target = json!({ "pax": "Resenärer", "pax_old": "Taken" });
+
+        let result = fix_shape_conflicts(&reference, &mut target).unwrap();
+
+        assert_eq!(
+            result,
+            vec![(
+                KeyPath::parse("pax").unwrap(),
+                KeyPath::parse("pax_old2").unwrap()
+            )]
+        );
+        assert_eq!(
+            target,
+            json!({ "pax": {}, "pax_old": "Taken", "pax_old2": "Resenärer" })
+        );
+    }
+
+    #[test]
+    fn should_leave_text_missing_when_target_has_object() {
+        let reference = json!({ "pax": "Guests" });
+        let mut target = json!({ "pax": { "rooms": "Rum" } });
+
+        fix_shape_conflicts(&reference, &mut target).unwrap();
+
+        assert_eq!(target, json!({ "pax_old": { "rooms": "Rum" } }));
+    }
+
+    #[test]
+    fn should_keep_index_segments_in_new_path() {
+        let reference = json!({ "items": [{ "pax": { "rooms": "Rooms" } }] });
+        let mut target = json!({ "items": [{ "pax": "Resenärer" }] });
+
+        let result = fix_shape_conflicts(&reference, &mut target).unwrap();
+
+        assert_eq!(result[0].1.to_string(), "items[0].pax_old");
+    }
+}
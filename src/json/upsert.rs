@@ -0,0 +1,179 @@
+use crate::json::insert::insert_under_key;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+use crate::json::replace::replace_value_at_key;
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Upserted {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+impl fmt::Display for Upserted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Upserted::Created => "created",
+            Upserted::Updated => "updated",
+            Upserted::Unchanged => "unchanged",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Inserts value under key, or replaces value already present there.
+pub fn upsert_value_at_key(
+    json: &mut Value,
+    path: &[&str],
+    key: &str,
+    new_value: impl Into<Value>,
+) -> Result<Upserted, String> {
+    let new_value = new_value.into();
+    let full_path = [path, &[key]].concat();
+
+    match get_value_at_path(json, &full_path) {
+        Some(existing) if *existing == new_value => Ok(Upserted::Unchanged),
+        Some(_) => {
+            replace_value_at_key(json, path, key, new_value)?;
+            Ok(Upserted::Updated)
+        }
+        None => {
+            insert_under_key(json, path, key, new_value)?;
+            Ok(Upserted::Created)
+        }
+    }
+}
+
+/// Upserts value under key. Object value is upserted leaf by leaf, so keys
+/// already present under key, but missing in value, are kept.
+pub fn upsert_tree_under_key(
+    json: &mut Value,
+    path: &[&str],
+    key: &str,
+    new_value: Value,
+) -> Result<Vec<(KeyPath, Upserted)>, String> {
+    let base = path
+        .iter()
+        .fold(KeyPath::default(), |base, segment| base.child(segment))
+        .child(key);
+
+    if !new_value.is_object() {
+        let result = upsert_value_at_key(json, path, key, new_value)?;
+        return Ok(vec![(base, result)]);
+    }
+
+    let mut results = Vec::new();
+    for leaf in get_json_paths(&new_value) {
+        let leaf_segments = leaf.segments();
+        let value = get_value_at_path(&new_value, &leaf_segments)
+            .cloned()
+            .unwrap_or(Value::Null);
+        let full_path = leaf_segments
+            .iter()
+            .fold(base.clone(), |full_path, segment| full_path.child(segment));
+        let (leaf_parent, leaf_key) = full_path.parent_and_key()?;
+        let result = upsert_value_at_key(json, &leaf_parent, leaf_key, value)?;
+        results.push((full_path, result));
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod upsert_value_at_key {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_create_missing_key() {
+        let mut data = json!({});
+        let expected = json!({ "foo": { "key": "value" } });
+
+        let result = upsert_value_at_key(&mut data, &["foo"], "key", "value");
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), Upserted::Created);
+    }
+
+    #[test]
+    fn should_update_existing_key() {
+        let mut data = json!({ "key": "old_value" });
+        let expected = json!({ "key": "new_value" });
+
+        let result = upsert_value_at_key(&mut data, &[], "key", "new_value");
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), Upserted::Updated);
+    }
+
+    #[test]
+    fn should_report_unchanged_key() {
+        let mut data = json!({ "key": "value" });
+        let expected = json!({ "key": "value" });
+
+        let result = upsert_value_at_key(&mut data, &[], "key", "value");
+
+        assert_eq!(data, expected);
+        assert_eq!(result.unwrap(), Upserted::Unchanged);
+    }
+
+    #[test]
+    fn should_return_err_if_found_string_instead_of_object_during_path_traversal() {
+        let mut data = json!({ "foo": "foo_value" });
+
+        let result = upsert_value_at_key(&mut data, &["foo", "bar"], "key", "value");
+
+        assert_eq!(
+            result.unwrap_err(),
+            "Expected object at path segment 'foo', but found something else!"
+        );
+    }
+}
+
+#[cfg(test)]
+mod upsert_tree_under_key {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_upsert_object_leaf_by_leaf() {
+        let mut data = json!({
+            "feature": {
+                "title": "Title",
+                "body": "Old body",
+                "other": "Other"
+            }
+        });
+        let expected = json!({
+            "feature": {
+                "title": "Title",
+                "body": "New body",
+                "footer": "Footer",
+                "other": "Other"
+            }
+        });
+
+        let result = upsert_tree_under_key(
+            &mut data,
+            &[],
+            "feature",
+            json!({ "title": "Title", "body": "New body", "footer": "Footer" }),
+        );
+
+        assert_eq!(data, expected);
+        assert_eq!(
+            result.unwrap(),
+            vec![
+                (KeyPath::parse("feature.body").unwrap(), Upserted::Updated),
+                (KeyPath::parse("feature.footer").unwrap(), Upserted::Created),
+                (
+                    KeyPath::parse("feature.title").unwrap(),
+                    Upserted::Unchanged
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Manages JSON translation files, one file per locale in a directory.
+//!
+//! [`Catalog`] loads a directory and offers the operations of the `szambo`
+//! CLI: add, replace, remove, rename, compare and listing paths.
+//!
+//! ```no_run
+//! use szambo::{Catalog, KeyPath};
+//!
+//! let mut catalog = Catalog::open("lang/")?;
+//! catalog.set("en", &KeyPath::parse("pax.rooms")?, "Rooms")?;
+//! for diff in catalog.compare("en")? {
+//!     println!("{}", diff);
+//! }
+//! catalog.save()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! # Stability
+//!
+//! Items exported from the crate root follow semver. Modules hidden from the
+//! documentation are used by the `szambo` binary and may change in any
+//! release.
+
+mod catalog;
+mod error;
+#[cfg(test)]
+mod test_support;
+
+#[doc(hidden)]
+pub mod batch;
+#[doc(hidden)]
+pub mod blame;
+#[doc(hidden)]
+pub mod check;
+#[doc(hidden)]
+pub mod codegen;
+#[doc(hidden)]
+pub mod files;
+#[doc(hidden)]
+pub mod git;
+#[doc(hidden)]
+pub mod glossary;
+#[doc(hidden)]
+pub mod json;
+#[doc(hidden)]
+pub mod lsp;
+#[doc(hidden)]
+pub mod memory;
+#[doc(hidden)]
+pub mod pseudo;
+#[doc(hidden)]
+pub mod search;
+#[doc(hidden)]
+pub mod serve;
+#[doc(hidden)]
+pub mod translate;
+#[doc(hidden)]
+pub mod tui;
+#[doc(hidden)]
+pub mod utils;
+#[doc(hidden)]
+pub mod watch;
+
+pub use catalog::Catalog;
+pub use error::Error;
+pub use json::compare::JsonDiff;
+pub use json::key_path::KeyPath;
+pub use json::rename::ConflictStrategy;
+pub use json::selector::Selector;
+pub use json::shape::ShapeConflict;
+pub use json::upsert::Upserted;
@@ -0,0 +1,821 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{
+    CodeActionRequest, Completion, ExecuteCommand, GotoDefinition, HoverRequest, Request as _,
+};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, Command, CompletionItem, CompletionItemKind, CompletionOptions,
+    CompletionParams, CompletionResponse, CompletionTextEdit, Diagnostic, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, ExecuteCommandOptions, ExecuteCommandParams, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, Location,
+    MarkupContent, MarkupKind, NumberOrString, OneOf, Position, PublishDiagnosticsParams, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+};
+use regex::Regex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::Catalog;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+use crate::utils;
+
+const ADD_KEY_COMMAND: &str = "szambo.addKey";
+const MISSING_KEY: &str = "missing-key";
+
+// Opening of a translation call up to the key: `t('`, `$t("`, `i18n.t(``.
+const TRANSLATION_CALL: &str = r#"(?:^|[^\w$])\$?t\(\s*['"`]([^'"`]*)"#;
+
+/// Key passed as a string literal to a translation call.
+#[derive(Debug, PartialEq)]
+struct KeyUsage {
+    key: KeyPath,
+    range: Range,
+}
+
+struct Server {
+    locale_dir: PathBuf,
+    reference: Option<String>,
+    catalog: Catalog,
+    documents: HashMap<Url, String>,
+}
+
+/// Runs language server over stdio until the client shuts it down.
+pub fn run(where_: &str, reference: Option<String>) -> Result<(), String> {
+    let mut server = Server::load(where_, reference)?;
+    let (connection, io_threads) = Connection::stdio();
+    server.serve(&connection)?;
+
+    // Writer thread stops only once every sender is gone.
+    drop(connection);
+    io_threads.join().map_err(|e| e.to_string())
+}
+
+impl Server {
+    fn load(where_: &str, reference: Option<String>) -> Result<Self, String> {
+        let locale_dir =
+            fs::canonicalize(where_).map_err(|e| format!("Can't open {}: {}", where_, e))?;
+        let mut server = Server {
+            locale_dir,
+            reference: reference.map(utils::get_file_stem).transpose()?,
+            catalog: Catalog::new([]),
+            documents: HashMap::new(),
+        };
+        server.load_locales()?;
+        if let Some(reference) = &server.reference
+            && server.reference().is_none()
+        {
+            return Err(format!(
+                "Reference locale {} not found in {}",
+                reference, where_
+            ));
+        }
+        Ok(server)
+    }
+
+    /// Handles messages of an initialized connection until shutdown.
+    fn serve(&mut self, connection: &Connection) -> Result<(), String> {
+        let capabilities = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(vec!["'".into(), "\"".into(), "`".into(), ".".into()]),
+                ..Default::default()
+            }),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            definition_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+            execute_command_provider: Some(ExecuteCommandOptions {
+                commands: vec![ADD_KEY_COMMAND.to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        connection
+            .initialize(serde_json::to_value(capabilities).unwrap())
+            .map_err(|e| e.to_string())?;
+
+        for message in &connection.receiver {
+            let outgoing = match message {
+                Message::Request(request) => {
+                    if connection
+                        .handle_shutdown(&request)
+                        .map_err(|e| e.to_string())?
+                    {
+                        break;
+                    }
+                    self.handle_request(request)
+                }
+                Message::Notification(notification) => self.handle_notification(notification),
+                Message::Response(_) => Vec::new(),
+            };
+
+            for message in outgoing {
+                connection.sender.send(message).map_err(|e| e.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load_locales(&mut self) -> Result<(), String> {
+        self.catalog = Catalog::open(&self.locale_dir).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Locale used for completion and go-to-definition: the one given on
+    /// command line, or the default one, see [`Catalog::reference`].
+    fn reference(&self) -> Option<&str> {
+        self.catalog.reference(self.reference.as_deref()).ok()
+    }
+
+    fn handle_request(&mut self, request: Request) -> Vec<Message> {
+        let id = request.id.clone();
+        let response = match request.method.as_str() {
+            Completion::METHOD => respond(id, request, |params| self.completion(params)),
+            HoverRequest::METHOD => respond(id, request, |params| self.hover(params)),
+            GotoDefinition::METHOD => respond(id, request, |params| self.definition(params)),
+            CodeActionRequest::METHOD => respond(id, request, |params| self.code_actions(params)),
+            ExecuteCommand::METHOD => {
+                let response = respond(id, request, |params| self.execute_command(params));
+                let mut messages = vec![Message::Response(response)];
+                messages.extend(self.publish_all_diagnostics());
+                return messages;
+            }
+            _ => Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request '{}'", request.method),
+            ),
+        };
+
+        vec![Message::Response(response)]
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Vec<Message> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Ok(params) =
+                    serde_json::from_value::<DidOpenTextDocumentParams>(notification.params)
+                else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), params.text_document.text);
+                self.publish_diagnostics(&uri).into_iter().collect()
+            }
+            DidChangeTextDocument::METHOD => {
+                let Ok(params) =
+                    serde_json::from_value::<DidChangeTextDocumentParams>(notification.params)
+                else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                // Full sync, the last change holds the whole document.
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(uri.clone(), change.text);
+                }
+                self.publish_diagnostics(&uri).into_iter().collect()
+            }
+            DidCloseTextDocument::METHOD => {
+                let Ok(params) =
+                    serde_json::from_value::<DidCloseTextDocumentParams>(notification.params)
+                else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                vec![diagnostics_notification(uri, Vec::new())]
+            }
+            DidSaveTextDocument::METHOD => {
+                let Ok(params) =
+                    serde_json::from_value::<DidSaveTextDocumentParams>(notification.params)
+                else {
+                    return Vec::new();
+                };
+                let is_locale = params
+                    .text_document
+                    .uri
+                    .to_file_path()
+                    .ok()
+                    .and_then(|path| fs::canonicalize(path).ok())
+                    .is_some_and(|path| path.parent() == Some(&self.locale_dir));
+                // Broken locale file keeps the last loaded version.
+                if is_locale && self.load_locales().is_ok() {
+                    return self.publish_all_diagnostics();
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>, String> {
+        let position = params.text_document_position.position;
+        let Some(line) = self.line(&params.text_document_position.text_document.uri, position)
+        else {
+            return Ok(None);
+        };
+        let Some(start) = key_start_before(line, position.character) else {
+            return Ok(None);
+        };
+        let Some(reference) = self.reference() else {
+            return Ok(None);
+        };
+        let reference = self.catalog.json(reference).map_err(|e| e.to_string())?;
+
+        let range = Range::new(Position::new(position.line, start), position);
+        let items = get_json_paths(reference)
+            .into_iter()
+            .map(|path| {
+                let key = path.to_string();
+                CompletionItem {
+                    label: key.clone(),
+                    kind: Some(CompletionItemKind::CONSTANT),
+                    detail: get_value_at_path(reference, &path.segments()).map(display_value),
+                    filter_text: Some(key.clone()),
+                    text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, key))),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+
+    fn hover(&self, params: HoverParams) -> Result<Option<Hover>, String> {
+        let position = params.text_document_position_params;
+        let Some(usage) = self.usage_at(&position.text_document.uri, position.position) else {
+            return Ok(None);
+        };
+
+        let mut lines = vec![format!("**{}**\n", usage.key)];
+        for locale in self.catalog.locales() {
+            let value = self
+                .catalog
+                .get(locale, &usage.key)
+                .map_err(|e| e.to_string())?
+                .map(display_value)
+                .unwrap_or_else(|| "*missing*".to_string());
+            lines.push(format!("- `{}`: {}", self.file_name(locale), value));
+        }
+
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: lines.join("\n"),
+            }),
+            range: Some(usage.range),
+        }))
+    }
+
+    fn definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>, String> {
+        let position = params.text_document_position_params;
+        let Some(usage) = self.usage_at(&position.text_document.uri, position.position) else {
+            return Ok(None);
+        };
+        let Some(reference) = self.reference() else {
+            return Ok(None);
+        };
+        let file = self.catalog.file(reference).map_err(|e| e.to_string())?;
+
+        let text = fs::read_to_string(file).map_err(|e| e.to_string())?;
+        let Some(key_position) = find_key_position(&text, &usage.key) else {
+            return Ok(None);
+        };
+        let uri = Url::from_file_path(file).map_err(|_| format!("Invalid path {:?}", file))?;
+
+        Ok(Some(GotoDefinitionResponse::Scalar(Location::new(
+            uri,
+            Range::new(key_position, key_position),
+        ))))
+    }
+
+    fn code_actions(
+        &self,
+        params: CodeActionParams,
+    ) -> Result<Option<Vec<CodeActionOrCommand>>, String> {
+        let actions = params
+            .context
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| {
+                diagnostic.code == Some(NumberOrString::String(MISSING_KEY.into()))
+            })
+            .filter_map(|diagnostic| {
+                let key = diagnostic.data.as_ref()?.as_str()?;
+                let title = format!("Add '{}' to missing locales", key);
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: title.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    command: Some(Command::new(
+                        title,
+                        ADD_KEY_COMMAND.to_string(),
+                        Some(vec![Value::String(key.to_string())]),
+                    )),
+                    ..Default::default()
+                }))
+            })
+            .collect();
+
+        Ok(Some(actions))
+    }
+
+    fn execute_command(&mut self, params: ExecuteCommandParams) -> Result<Option<Value>, String> {
+        if params.command != ADD_KEY_COMMAND {
+            return Err(format!("Unknown command '{}'", params.command));
+        }
+        let key = params
+            .arguments
+            .first()
+            .and_then(Value::as_str)
+            .ok_or_else(|| "Missing key argument".to_string())?;
+        let key = KeyPath::parse(key)?;
+
+        // Add an empty value, so `check` still reports it as untranslated.
+        // A conflict in one locale leaves all files untouched.
+        let values: Vec<(String, Value)> = self
+            .catalog
+            .locales()
+            .filter(|locale| matches!(self.catalog.get(locale, &key), Ok(None)))
+            .map(|locale| (locale.to_string(), Value::from("")))
+            .collect();
+        self.catalog.add(&key, values).map_err(|e| e.to_string())?;
+        self.catalog.save().map_err(|e| e.to_string())?;
+
+        Ok(None)
+    }
+
+    fn diagnostics(&self, text: &str) -> Vec<Diagnostic> {
+        find_usages(text)
+            .into_iter()
+            .filter_map(|usage| {
+                let missing = self.missing_locales(&usage.key);
+                if missing.is_empty() {
+                    return None;
+                }
+                let (severity, message) = if missing.len() == self.catalog.locales().count() {
+                    let message = format!("Key '{}' doesn't exist in any locale", usage.key);
+                    (DiagnosticSeverity::ERROR, message)
+                } else {
+                    let message =
+                        format!("Key '{}' is missing in {}", usage.key, missing.join(", "));
+                    (DiagnosticSeverity::WARNING, message)
+                };
+                Some(Diagnostic {
+                    range: usage.range,
+                    severity: Some(severity),
+                    code: Some(NumberOrString::String(MISSING_KEY.into())),
+                    source: Some("szambo".into()),
+                    message,
+                    data: Some(Value::String(usage.key.to_string())),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    fn missing_locales(&self, key: &KeyPath) -> Vec<String> {
+        self.catalog
+            .locales()
+            .filter(|locale| matches!(self.catalog.get(locale, key), Ok(None)))
+            .map(|locale| self.file_name(locale))
+            .collect()
+    }
+
+    fn file_name(&self, locale: &str) -> String {
+        self.catalog
+            .file(locale)
+            .ok()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn publish_diagnostics(&self, uri: &Url) -> Option<Message> {
+        let text = self.documents.get(uri)?;
+        Some(diagnostics_notification(
+            uri.clone(),
+            self.diagnostics(text),
+        ))
+    }
+
+    fn publish_all_diagnostics(&self) -> Vec<Message> {
+        self.documents
+            .keys()
+            .filter_map(|uri| self.publish_diagnostics(uri))
+            .collect()
+    }
+
+    fn line(&self, uri: &Url, position: Position) -> Option<&str> {
+        self.documents.get(uri)?.lines().nth(position.line as usize)
+    }
+
+    fn usage_at(&self, uri: &Url, position: Position) -> Option<KeyUsage> {
+        find_usages(self.documents.get(uri)?)
+            .into_iter()
+            .find(|usage| usage.range.start <= position && position <= usage.range.end)
+    }
+}
+
+/// Deserializes request params and turns handler result into a response.
+fn respond<P: DeserializeOwned, R: Serialize>(
+    id: RequestId,
+    request: Request,
+    handler: impl FnOnce(P) -> Result<R, String>,
+) -> Response {
+    let params = match serde_json::from_value(request.params) {
+        Ok(params) => params,
+        Err(e) => return Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+    };
+
+    match handler(params) {
+        Ok(result) => Response::new_ok(id, result),
+        Err(message) => Response::new_err(id, ErrorCode::RequestFailed as i32, message),
+    }
+}
+
+fn diagnostics_notification(uri: Url, diagnostics: Vec<Diagnostic>) -> Message {
+    let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+    Message::Notification(Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        params,
+    ))
+}
+
+/// Finds keys passed as string literals to translation calls.
+fn find_usages(text: &str) -> Vec<KeyUsage> {
+    let call = Regex::new(&format!("{}['\"`]", TRANSLATION_CALL)).unwrap();
+    let mut usages = Vec::new();
+
+    for (line_number, line) in text.lines().enumerate() {
+        for captures in call.captures_iter(line) {
+            let found = captures.get(1).unwrap();
+            // Template literals with interpolation aren't static keys.
+            if found.as_str().contains("${") {
+                continue;
+            }
+            let Ok(key) = KeyPath::parse(found.as_str()) else {
+                continue;
+            };
+            let line_number = line_number as u32;
+            usages.push(KeyUsage {
+                key,
+                range: Range::new(
+                    Position::new(line_number, utf16_column(line, found.start())),
+                    Position::new(line_number, utf16_column(line, found.end())),
+                ),
+            });
+        }
+    }
+
+    usages
+}
+
+/// Returns column where key being typed starts, if cursor is inside the
+/// string literal of a translation call.
+fn key_start_before(line: &str, character: u32) -> Option<u32> {
+    let prefix = &line[..byte_offset(line, character)];
+    let call = Regex::new(&format!("{}$", TRANSLATION_CALL)).unwrap();
+    let found = call.captures(prefix)?.get(1)?;
+    Some(utf16_column(line, found.start()))
+}
+
+/// Finds where key is defined in pretty-printed JSON text, looking for each
+/// segment after the previous one.
+fn find_key_position(text: &str, key: &KeyPath) -> Option<Position> {
+    let mut start = 0;
+    let mut offset = 0;
+
+    for segment in key.segments() {
+        let quoted = serde_json::to_string(segment).unwrap();
+        let pattern = Regex::new(&format!(r"{}\s*:", regex::escape(&quoted))).unwrap();
+        let found = pattern.find_at(text, offset)?;
+        start = found.start();
+        offset = found.end();
+    }
+
+    let line = text[..start].matches('\n').count() as u32;
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let column = utf16_column(&text[line_start..], start - line_start);
+    Some(Position::new(line, column))
+}
+
+/// LSP counts columns in UTF-16 code units.
+fn utf16_column(line: &str, byte: usize) -> u32 {
+    line[..byte].encode_utf16().count() as u32
+}
+
+fn byte_offset(line: &str, column: u32) -> usize {
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= column {
+            return i;
+        }
+        units += c.len_utf16() as u32;
+    }
+    line.len()
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod find_usages {
+    use super::*;
+
+    #[test]
+    fn should_find_keys_in_translation_calls() {
+        let text = "const a = t('pax.rooms');\n  {{ $t(\"pax.adults\") }} i18n.t(`foo`)";
+
+        let result = find_usages(text);
+
+        assert_eq!(
+            result,
+            vec![
+                KeyUsage {
+                    key: KeyPath::parse("pax.rooms").unwrap(),
+                    range: Range::new(Position::new(0, 13), Position::new(0, 22)),
+                },
+                KeyUsage {
+                    key: KeyPath::parse("pax.adults").unwrap(),
+                    range: Range::new(Position::new(1, 9), Position::new(1, 19)),
+                },
+                KeyUsage {
+                    key: KeyPath::parse("foo").unwrap(),
+                    range: Range::new(Position::new(1, 33), Position::new(1, 36)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_other_calls_and_interpolated_keys() {
+        let text = "format('pax.rooms'); t(`pax.${kind}`)";
+
+        let result = find_usages(text);
+
+        assert_eq!(result, vec![]);
+    }
+}
+
+#[cfg(test)]
+mod key_start_before {
+    use super::*;
+
+    #[test]
+    fn should_return_start_of_key_being_typed() {
+        assert_eq!(key_start_before("t('pax.ro')", 9), Some(3));
+    }
+
+    #[test]
+    fn should_return_none_outside_of_translation_call() {
+        assert_eq!(key_start_before("log('pax.ro')", 11), None);
+    }
+}
+
+#[cfg(test)]
+mod find_key_position {
+    use super::*;
+
+    #[test]
+    fn should_find_nested_key() {
+        let text = "{\n  \"foo\": \"bar\",\n  \"pax\": {\n    \"foo\": \"baz\"\n  }\n}";
+
+        let result = find_key_position(text, &KeyPath::parse("pax.foo").unwrap());
+
+        assert_eq!(result, Some(Position::new(3, 4)));
+    }
+
+    #[test]
+    fn should_return_none_for_missing_key() {
+        let result = find_key_position("{}", &KeyPath::parse("pax").unwrap());
+
+        assert_eq!(result, None);
+    }
+}
+
+#[cfg(test)]
+mod load {
+    use super::*;
+    use crate::test_support::locale_dir;
+    use serde_json::json;
+
+    #[test]
+    fn should_accept_reference_locale_with_or_without_extension() {
+        let dir = locale_dir(
+            "lsp-reference",
+            &[("en.json", json!({ "a": "A" })), ("sv.json", json!({}))],
+        );
+
+        for reference in ["sv", "sv.json"] {
+            let server = Server::load(dir.to_str().unwrap(), Some(reference.to_string())).unwrap();
+
+            assert_eq!(server.reference(), Some("sv"));
+        }
+    }
+
+    #[test]
+    fn should_fail_for_unknown_reference_locale() {
+        let dir = locale_dir("lsp-unknown-reference", &[("en.json", json!({}))]);
+
+        let result = Server::load(dir.to_str().unwrap(), Some("de".to_string()));
+
+        assert!(result.is_err_and(|e| e.starts_with("Reference locale de not found")));
+    }
+}
+
+#[cfg(test)]
+mod execute_command {
+    use super::*;
+    use crate::files;
+    use crate::test_support::locale_dir;
+    use serde_json::json;
+
+    fn add_key(key: &str) -> ExecuteCommandParams {
+        ExecuteCommandParams {
+            command: ADD_KEY_COMMAND.to_string(),
+            arguments: vec![json!(key)],
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    #[test]
+    fn should_add_empty_value_to_locales_missing_key() {
+        let dir = locale_dir(
+            "lsp-add",
+            &[
+                (
+                    "en.json",
+                    json!({ "pax": { "rooms": "Rooms", "adults": "Adults" } }),
+                ),
+                ("sv.json", json!({ "pax": { "rooms": "Rum" } })),
+            ],
+        );
+        let mut server = Server::load(dir.to_str().unwrap(), None).unwrap();
+
+        let result = server.execute_command(add_key("pax.adults"));
+
+        assert_eq!(result, Ok(None));
+        assert_eq!(
+            files::load_json_into_value(&dir.join("sv.json")).unwrap(),
+            json!({ "pax": { "rooms": "Rum", "adults": "" } })
+        );
+    }
+
+    #[test]
+    fn should_leave_every_file_untouched_on_conflict() {
+        let dir = locale_dir(
+            "lsp-conflict",
+            &[
+                ("en.json", json!({ "pax": { "rooms": "Rooms" } })),
+                ("sv.json", json!({ "pax": "Rum" })),
+            ],
+        );
+        let mut server = Server::load(dir.to_str().unwrap(), None).unwrap();
+
+        let result = server.execute_command(add_key("pax.adults"));
+
+        assert!(result.is_err());
+        assert_eq!(
+            files::load_json_into_value(&dir.join("en.json")).unwrap(),
+            json!({ "pax": { "rooms": "Rooms" } })
+        );
+        assert_eq!(
+            server.catalog.json("en").unwrap(),
+            &json!({ "pax": { "rooms": "Rooms" } })
+        );
+    }
+}
+
+#[cfg(test)]
+mod serve {
+    use super::*;
+    use crate::files;
+    use crate::test_support::locale_dir;
+    use lsp_types::notification::{Exit, Initialized};
+    use lsp_types::request::{Initialize, Shutdown};
+    use serde_json::json;
+    use std::thread;
+
+    fn request(id: i32, method: &str, params: Value) -> Message {
+        Message::Request(Request::new(id.into(), method.to_string(), params))
+    }
+
+    fn notification(method: &str, params: Value) -> Message {
+        Message::Notification(Notification::new(method.to_string(), params))
+    }
+
+    fn response(client: &Connection, id: i32) -> Response {
+        loop {
+            match client.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id.into() => return response,
+                _ => continue,
+            }
+        }
+    }
+
+    #[test]
+    fn should_complete_keys_and_add_missing_one() {
+        let dir = locale_dir(
+            "lsp-serve",
+            &[
+                (
+                    "en.json",
+                    json!({ "pax": { "rooms": "Rooms", "adults": "Adults" } }),
+                ),
+                ("sv.json", json!({ "pax": { "rooms": "Rum" } })),
+            ],
+        );
+        let mut server = Server::load(dir.to_str().unwrap(), None).unwrap();
+        let (connection, client) = Connection::memory();
+        let handle = thread::spawn(move || server.serve(&connection));
+        let uri = "file:///app/main.js";
+
+        client
+            .sender
+            .send(request(
+                1,
+                Initialize::METHOD,
+                json!({ "capabilities": {} }),
+            ))
+            .unwrap();
+        let initialized = response(&client, 1);
+        client
+            .sender
+            .send(notification(Initialized::METHOD, json!({})))
+            .unwrap();
+        client
+            .sender
+            .send(notification(
+                DidOpenTextDocument::METHOD,
+                json!({ "textDocument": {
+                    "uri": uri, "languageId": "javascript", "version": 1, "text": "t('pax.')"
+                } }),
+            ))
+            .unwrap();
+        client
+            .sender
+            .send(request(
+                2,
+                Completion::METHOD,
+                json!({ "textDocument": { "uri": uri }, "position": { "line": 0, "character": 7 } }),
+            ))
+            .unwrap();
+        let completion = response(&client, 2);
+        client
+            .sender
+            .send(request(
+                3,
+                ExecuteCommand::METHOD,
+                json!({ "command": ADD_KEY_COMMAND, "arguments": ["pax.adults"] }),
+            ))
+            .unwrap();
+        let added = response(&client, 3);
+        client
+            .sender
+            .send(request(4, Shutdown::METHOD, Value::Null))
+            .unwrap();
+        response(&client, 4);
+        client
+            .sender
+            .send(notification(Exit::METHOD, Value::Null))
+            .unwrap();
+
+        assert_eq!(handle.join().unwrap(), Ok(()));
+        assert!(initialized.result.unwrap()["capabilities"]["completionProvider"].is_object());
+        let labels: Vec<Value> = completion
+            .result
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].clone())
+            .collect();
+        assert_eq!(labels, vec![json!("pax.adults"), json!("pax.rooms")]);
+        assert_eq!(added.result, Some(Value::Null));
+        assert_eq!(
+            files::load_json_into_value(&dir.join("sv.json")).unwrap(),
+            json!({ "pax": { "rooms": "Rum", "adults": "" } })
+        );
+    }
+}
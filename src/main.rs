@@ -1,12 +1,9 @@
 mod commands;
-mod files;
-mod json;
 mod parser;
-mod search;
-mod utils;
 
 use clap::Parser;
-use parser::{Cli, Commands};
+use parser::{Cli, Commands, SchemaCommand};
+use szambo::ConflictStrategy;
 
 fn main() {
     let cli = Cli::parse();
@@ -17,9 +14,14 @@ fn main() {
             from,
             where_,
             files,
-        } => commands::add_to_many_command(key, from, where_, files),
+            upsert,
+        } => commands::add_to_many_command(key, from, where_, files, upsert),
 
-        Commands::AddToSingle { from, where_ } => commands::add_to_single_command(from, where_),
+        Commands::AddToSingle {
+            from,
+            where_,
+            upsert,
+        } => commands::add_to_single_command(from, where_, upsert),
 
         Commands::Remove { key, where_ } => commands::remove_command(key, where_),
 
@@ -28,15 +30,120 @@ fn main() {
             from,
             where_,
             files,
-        } => commands::replace_command(key, from, where_, files),
+            upsert,
+        } => commands::replace_command(key, from, where_, files, upsert),
+
+        Commands::Rename {
+            from,
+            to,
+            where_,
+            overwrite,
+            merge,
+        } => {
+            let strategy = if overwrite {
+                ConflictStrategy::Overwrite
+            } else if merge {
+                ConflictStrategy::Merge
+            } else {
+                ConflictStrategy::Fail
+            };
+            commands::rename_command(from, to, where_, strategy)
+        }
+
+        Commands::Copy {
+            from,
+            to,
+            where_,
+            to_where,
+        } => commands::copy_command(from, to, where_, to_where),
+
+        Commands::Move {
+            from,
+            to,
+            where_,
+            to_where,
+        } => commands::move_command(from, to, where_, to_where),
+
+        Commands::Apply { from, where_ } => commands::apply_command(from, where_),
+
+        Commands::Merge {
+            base,
+            ours,
+            theirs,
+            output,
+            conflicts,
+        } => commands::merge_command(base, ours, theirs, output, conflicts),
+
+        Commands::Diff { from, to, where_ } => commands::diff_command(from, to, where_),
+
+        Commands::Check {
+            where_,
+            staged,
+            source,
+            glossary,
+            reference,
+        } => commands::check_command(where_, staged, source, glossary, reference),
+
+        Commands::Blame { key, where_ } => commands::blame_command(key, where_),
+
+        Commands::Watch { where_, source } => commands::watch_command(where_, source),
+
+        Commands::Lsp { where_, reference } => commands::lsp_command(where_, reference),
+
+        Commands::Tui { where_ } => commands::tui_command(where_),
+
+        Commands::Serve {
+            where_,
+            port,
+            token,
+        } => commands::serve_command(where_, port, token),
 
-        Commands::Rename { from, to, where_ } => commands::rename_command(from, to, where_),
+        Commands::Codegen {
+            lang,
+            reference,
+            output,
+            check,
+        } => commands::codegen_command(lang, reference, output, check),
 
+        Commands::Schema { command } => match command {
+            SchemaCommand::Generate { reference, output } => {
+                commands::schema_generate_command(reference, output)
+            }
+            SchemaCommand::Validate { schema, where_ } => {
+                commands::schema_validate_command(schema, where_)
+            }
+        },
+
+        Commands::Pseudo {
+            where_,
+            from,
+            to,
+            padding,
+        } => commands::pseudo_command(where_, from, to, padding),
+        Commands::Translate {
+            where_,
+            to,
+            from,
+            overwrite,
+            provider,
+            metadata,
+        } => commands::translate_command(where_, from, to, overwrite, provider, metadata),
+        Commands::FillFromMemory {
+            where_,
+            reference,
+            threshold,
+        } => commands::fill_from_memory_command(where_, reference, threshold),
+        Commands::FixShape { where_, reference } => commands::fix_shape_command(where_, reference),
         Commands::Sort { where_ } => commands::sort_command(where_),
 
         Commands::Compare { reference, target } => commands::compare_command(target, reference),
 
-        Commands::CompareAll { where_ } => commands::compare_all_command(where_),
+        Commands::CompareAll {
+            where_,
+            suggest,
+            glossary,
+            reference,
+        } => commands::compare_all_command(where_, suggest, glossary, reference),
 
         Commands::ListUnusedKeys {
             translations,
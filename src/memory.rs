@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+
+/// Minimal score of suggestions shown for missing keys.
+pub const SUGGESTION_SCORE: u8 = 70;
+
+/// Translations of reference texts into one locale, collected from keys
+/// translated in both.
+#[derive(Debug, Default)]
+pub struct Memory {
+    /// Reference text, then its translations and how many keys use each.
+    translations: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Reference text the translation was made for.
+    pub source: String,
+    pub translation: String,
+    /// Similarity of source to looked up text in percent, 100 when equal.
+    pub score: u8,
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} ({}% match of {:?})",
+            self.translation, self.score, self.source
+        )
+    }
+}
+
+impl Memory {
+    /// Collects texts of reference and target at the same key. Empty and
+    /// untranslated (same as reference) texts are skipped.
+    pub fn build(reference: &Value, target: &Value) -> Self {
+        let mut memory = Memory::default();
+        for path in get_json_paths(reference) {
+            let segments = path.segments();
+            let source = get_value_at_path(reference, &segments).and_then(Value::as_str);
+            let translation = get_value_at_path(target, &segments).and_then(Value::as_str);
+            if let (Some(source), Some(translation)) = (source, translation)
+                && !translation.trim().is_empty()
+                && source != translation
+            {
+                *memory
+                    .translations
+                    .entry(source.to_string())
+                    .or_default()
+                    .entry(translation.to_string())
+                    .or_default() += 1;
+            }
+        }
+        memory
+    }
+
+    /// Returns translations of texts similar to given one by at least
+    /// `min_score` percent, best first. Every text gives its most used
+    /// translation. Scores above 100 are treated as 100.
+    pub fn suggest(&self, text: &str, min_score: u8) -> Vec<Suggestion> {
+        // Only equal texts score 100, so there is nothing to compare.
+        if min_score >= 100 {
+            return self
+                .translations
+                .get_key_value(text)
+                .and_then(|(source, translations)| suggestion(source, translations, 100))
+                .into_iter()
+                .collect();
+        }
+
+        let mut suggestions: Vec<Suggestion> = self
+            .translations
+            .iter()
+            .filter_map(|(source, translations)| {
+                suggestion(source, translations, similarity(source, text, min_score)?)
+            })
+            .collect();
+        suggestions.sort_by(|a, b| b.score.cmp(&a.score).then(a.source.cmp(&b.source)));
+        suggestions
+    }
+}
+
+/// Suggests the most used translation, the first alphabetically on a tie.
+fn suggestion(
+    source: &str,
+    translations: &BTreeMap<String, usize>,
+    score: u8,
+) -> Option<Suggestion> {
+    let (translation, _) = translations
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))?;
+    Some(Suggestion {
+        source: source.to_string(),
+        translation: translation.clone(),
+        score,
+    })
+}
+
+/// Percent of characters that don't have to be edited to turn one text into
+/// the other, or `None` when it's below `min_score`.
+fn similarity(a: &str, b: &str, min_score: u8) -> Option<u8> {
+    if a == b {
+        return Some(100);
+    }
+    let min_score = min_score.min(100);
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    let allowed = longest * (100 - min_score as usize) / 100;
+    if a.len().abs_diff(b.len()) > allowed {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    // Rounded down, so only equal texts score 100.
+    let score = 100 - (previous[b.len()] * 100).div_ceil(longest);
+    (score >= min_score as usize).then_some(score as u8)
+}
+
+#[cfg(test)]
+mod build {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_skip_empty_and_untranslated_texts() {
+        let reference = json!({ "a": "Cancel", "b": "Save", "c": "OK", "d": "Rooms" });
+        let target = json!({ "a": "Avbryt", "b": " ", "c": "OK" });
+
+        let result = Memory::build(&reference, &target);
+
+        assert_eq!(
+            result.translations,
+            BTreeMap::from([(
+                "Cancel".to_string(),
+                BTreeMap::from([("Avbryt".to_string(), 1)])
+            )])
+        );
+    }
+}
+
+#[cfg(test)]
+mod suggest {
+    use super::*;
+    use serde_json::json;
+
+    fn memory() -> Memory {
+        Memory::build(
+            &json!({ "a": "Save changes", "b": "Save changes", "c": "Save changes", "d": "Cancel" }),
+            &json!({ "a": "Spara", "b": "Spara ändringar", "c": "Spara ändringar", "d": "Avbryt" }),
+        )
+    }
+
+    #[test]
+    fn should_suggest_most_used_translation_of_equal_text() {
+        let result = memory().suggest("Save changes", 100);
+
+        assert_eq!(
+            result,
+            vec![Suggestion {
+                source: "Save changes".to_string(),
+                translation: "Spara ändringar".to_string(),
+                score: 100,
+            }]
+        );
+    }
+
+    #[test]
+    fn should_suggest_similar_texts_best_first() {
+        let result = memory().suggest("Save change", 50);
+
+        assert_eq!(
+            result.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![r#""Spara ändringar" (91% match of "Save changes")"#]
+        );
+    }
+
+    #[test]
+    fn should_treat_score_above_100_as_exact_match() {
+        let result = memory().suggest("Save change", 150);
+
+        assert_eq!(result, vec![]);
+        assert_eq!(memory().suggest("Save changes", 150).len(), 1);
+    }
+}
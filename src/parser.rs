@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 /// szambo - A command-line tool to manage JSON translation files
 #[derive(Parser)]
@@ -30,6 +30,10 @@ pub enum Commands {
         /// List of files for partial update (e.g., en.json,sv.json)
         #[arg(long)]
         files: Option<String>,
+
+        /// Create key if it's missing, overwrite it if it exists
+        #[arg(long)]
+        upsert: bool,
     },
 
     /// Add entries from input file to single target file
@@ -41,11 +45,15 @@ pub enum Commands {
         /// Target file (e.g., en.json)
         #[arg(long)]
         where_: String,
+
+        /// Create key if it's missing, overwrite it if it exists
+        #[arg(long)]
+        upsert: bool,
     },
 
-    /// Remove entry by key from a directory
+    /// Remove entries matching key or selector from a directory
     Remove {
-        /// Key to remove (e.g., foo.bar.baz)
+        /// Key or selector to remove (e.g., foo.bar.baz, foo.*, **.tooltip, re:^foo\.(bar|baz)$)
         #[arg(long)]
         key: String,
 
@@ -71,21 +79,311 @@ pub enum Commands {
         /// List of files for partial update (e.g., en.json,sv.json)
         #[arg(long)]
         files: Option<String>,
+
+        /// Create key if it's missing, overwrite it if it exists
+        #[arg(long)]
+        upsert: bool,
     },
 
     /// Renames a key in every file in directory
     Rename {
-        /// Key to rename (e.g., foo.bar)
+        /// Key or selector to rename (e.g., foo.bar, foo.*, re:^foo\.(bar|baz)$)
+        #[arg(long)]
+        from: String,
+
+        /// New key, may refer to selector captures (e.g., baz, baz.$1)
+        #[arg(long)]
+        to: String,
+
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Replace value already present under new key
+        #[arg(long, conflicts_with = "merge")]
+        overwrite: bool,
+
+        /// Merge object into object already present under new key, fails if any value differs
+        #[arg(long)]
+        merge: bool,
+    },
+
+    /// Copies a key in every file in directory, optionally into files in another directory
+    Copy {
+        /// Key or selector to copy (e.g., foo.bar, foo.*, re:^foo\.(bar|baz)$)
+        #[arg(long)]
+        from: String,
+
+        /// Destination key, may refer to selector captures (e.g., baz, baz.$1)
+        #[arg(long)]
+        to: String,
+
+        /// Source directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Destination directory, files are matched by name (e.g., lang/booking/)
+        #[clap(long)]
+        to_where: Option<String>,
+    },
+
+    /// Moves a key in every file in directory, optionally into files in another directory
+    Move {
+        /// Key or selector to move (e.g., foo.bar, foo.*, re:^foo\.(bar|baz)$)
+        #[arg(long)]
+        from: String,
+
+        /// Destination key, may refer to selector captures (e.g., baz, baz.$1)
+        #[arg(long)]
+        to: String,
+
+        /// Source directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Destination directory, files are matched by name (e.g., lang/booking/)
+        #[clap(long)]
+        to_where: Option<String>,
+    },
+
+    /// Applies batch of add, replace, upsert, remove and rename operations to every file
+    /// in directory. Nothing is written if any operation fails
+    Apply {
+        /// Path to the batch file (e.g., batch.json, batch.yaml, batch.csv)
+        #[arg(long)]
+        from: String,
+
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+    },
+
+    /// Three-way merges translation files or directories key by key. Conflicting keys keep
+    /// the value from --ours and make the command exit with error status, so it can be
+    /// used as a git merge driver
+    Merge {
+        /// Common ancestor, file or directory (e.g., base/lang/)
+        #[arg(long)]
+        base: String,
+
+        /// Our version, file or directory (e.g., lang/)
+        #[arg(long)]
+        ours: String,
+
+        /// Their version, file or directory (e.g., their/lang/)
+        #[arg(long)]
+        theirs: String,
+
+        /// Where to write the result, defaults to --ours
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Sidecar file to write conflict markers to (e.g., lang.conflicts)
+        #[arg(long)]
+        conflicts: Option<String>,
+    },
+
+    /// Lists keys added, removed, renamed or changed in every file in directory between two
+    /// git revisions
+    Diff {
+        /// Older revision (e.g., main)
+        #[arg(long)]
+        from: String,
+
+        /// Newer revision (e.g., HEAD), defaults to working tree
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Directory with translation files (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+    },
+
+    /// Checks translation files in directory for missing keys, mismatched placeholders and
+    /// suspicious values. Command returns status code
+    #[command(alias = "lint")]
+    Check {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Only check keys touched by changes staged in git, for use in a pre-commit hook
+        #[arg(long)]
+        staged: bool,
+
+        /// With --staged, also check keys used in staged files from this directory (e.g., src/)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Also check use of approved terms from glossary file (e.g., glossary.json)
+        #[arg(long)]
+        glossary: Option<String>,
+
+        /// Locale placeholders and glossary terms are compared with, defaults to the one
+        /// with the most keys (e.g., en)
+        #[arg(long)]
+        reference: Option<String>,
+    },
+
+    /// Shows who changed value of a key in every file in directory and when, using git history
+    Blame {
+        /// Key to look up (e.g., pax.rooms)
+        #[arg(long)]
+        key: String,
+
+        /// Directory with translation files (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+    },
+
+    /// Watches translation files and source code, printing problems as they appear or get
+    /// resolved
+    Watch {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Directory, which will be scanned for translation usage
+        #[clap(long)]
+        source: String,
+    },
+
+    /// Runs a language server over stdio, giving editors completion, hover, go-to-definition,
+    /// diagnostics and quick fixes for translation keys used in source code
+    Lsp {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Locale used for completion and go-to-definition, defaults to the one with the
+        /// most keys (e.g., en)
+        #[arg(long)]
+        reference: Option<String>,
+    },
+
+    /// Opens every file in directory in a terminal editor with one column per file
+    Tui {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+    },
+
+    /// Serves a web editor and a JSON REST API for every file in directory
+    Serve {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Port to listen on, only on localhost
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// Require `Authorization: Bearer <TOKEN>` header on every API request
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Generates code with keys of reference file and parameters of their values
+    Codegen {
+        /// Language of generated code
+        #[arg(long, value_enum)]
+        lang: Lang,
+
+        /// Reference file (e.g., lang/en.json)
+        #[arg(long)]
+        reference: String,
+
+        /// Generated file (e.g., src/i18n.d.ts)
+        #[arg(long)]
+        output: String,
+
+        /// Only check that output is up to date, exit with error status if not
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Generates JSON Schema from reference file, or validates files against it
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommand,
+    },
+
+    /// Generates pseudo-locale file from reference one, with accented, padded
+    /// and bracketed texts, to spot hard-coded and truncated texts
+    Pseudo {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Reference locale (e.g., en)
         #[arg(long)]
         from: String,
 
-        /// Key to rename (e.g., baz)
+        /// Generated locale, its file is overwritten (e.g., qps-ploc)
         #[arg(long)]
         to: String,
 
+        /// Percentage of text length added as padding
+        #[arg(long, default_value_t = 30)]
+        padding: u8,
+    },
+
+    /// Prefills target file with machine translations of reference file texts. Translated
+    /// keys are listed in a metadata file for review
+    Translate {
         /// Target directory (e.g., lang/)
         #[clap(long)]
         where_: String,
+
+        /// Locale to translate to (e.g., sv)
+        #[arg(long)]
+        to: String,
+
+        /// Locale to translate from, defaults to the one with the most keys (e.g., en)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Translate every text again, overwriting existing translations, otherwise only
+        /// keys missing in target are translated
+        #[arg(long)]
+        overwrite: bool,
+
+        #[command(flatten)]
+        provider: ProviderArgs,
+
+        /// File listing machine translated keys and their source texts, defaults to
+        /// <where>.machine.json (e.g., lang.machine.json)
+        #[arg(long)]
+        metadata: Option<String>,
+    },
+
+    /// Fills keys missing in every file with translations of the same reference text used
+    /// for other keys
+    FillFromMemory {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// Reference locale, defaults to the one with the most keys (e.g., en)
+        #[arg(long)]
+        reference: Option<String>,
+
+        /// Minimal similarity of reference texts in percent, 100 applies exact matches only
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u8).range(1..=100))]
+        threshold: u8,
+    },
+
+    /// Moves values of other type than in reference file aside, e.g. text where
+    /// reference has an object, so every file has the same structure
+    FixShape {
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+
+        /// File whose structure is kept, defaults to the one with the most keys
+        /// (e.g., en.json)
+        #[arg(long)]
+        reference: Option<String>,
     },
 
     /// Sorts every JSON file in directory alphabetically (using keys)
@@ -111,6 +409,19 @@ pub enum Commands {
         /// Target directory (e.g., lang/)
         #[clap(long)]
         where_: String,
+
+        /// Suggest translations of keys missing in target from translation memory
+        #[arg(long)]
+        suggest: bool,
+
+        /// Also fail on texts not using approved terms from glossary file (e.g., glossary.json)
+        #[arg(long)]
+        glossary: Option<String>,
+
+        /// Locale other files are compared with, defaults to the one with the most keys
+        /// (e.g., en)
+        #[arg(long)]
+        reference: Option<String>,
     },
 
     /// Reports which translation keys/paths are not used in source code. This command might
@@ -141,3 +452,69 @@ pub enum Commands {
         source: String,
     },
 }
+
+#[derive(Subcommand)]
+#[command(rename_all = "kebab-case")]
+pub enum SchemaCommand {
+    /// Generates JSON Schema requiring every key of reference file, with its type
+    Generate {
+        /// Reference file (e.g., lang/en.json)
+        #[arg(long)]
+        reference: String,
+
+        /// Generated schema (e.g., lang.schema.json)
+        #[arg(long)]
+        output: String,
+    },
+
+    /// Validates every file in directory against JSON Schema. Command returns status code
+    /// 1 when any file doesn't match
+    Validate {
+        /// Schema made by `schema generate`
+        #[arg(long)]
+        schema: String,
+
+        /// Target directory (e.g., lang/)
+        #[clap(long)]
+        where_: String,
+    },
+}
+
+/// Machine translation provider used by `translate`
+#[derive(Args)]
+pub struct ProviderArgs {
+    /// URL of HTTP translation API (e.g., https://api-free.deepl.com/v2/translate)
+    #[arg(long, required_unless_present = "command", conflicts_with = "command")]
+    pub endpoint: Option<String>,
+
+    /// Request and response shape of HTTP translation API
+    #[arg(long, value_enum, default_value_t = Api::Deepl)]
+    pub api: Api,
+
+    /// Key of HTTP translation API, defaults to SZAMBO_API_KEY environment variable
+    #[arg(long)]
+    pub api_key: Option<String>,
+
+    /// Shell command reading `{"from", "to", "texts"}` JSON on stdin and printing JSON
+    /// array of translations
+    #[arg(long)]
+    pub command: Option<String>,
+}
+
+/// Shape of HTTP translation API
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Api {
+    /// DeepL API v2
+    Deepl,
+    /// LibreTranslate, also self-hosted
+    Libretranslate,
+}
+
+/// Language of code generated by `codegen`
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Lang {
+    /// TypeScript declarations (.d.ts)
+    Ts,
+    /// Rust module with accessors for every locale in reference file's directory
+    Rust,
+}
@@ -0,0 +1,237 @@
+use regex::Regex;
+use serde_json::Value;
+
+use crate::check::PLACEHOLDER;
+
+const PLAIN: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const ACCENTED: &str = "åƀçðéƒĝĥîĵķļɱñöþǫŕšţûṽŵẋýžÅƁÇÐÉƑĜĤÎĴĶĻṀÑÖÞǪŔŠŢÛṼŴẊÝŽ";
+
+/// ICU argument types whose options hold messages, e.g.
+/// `{count, plural, one {# room} other {# rooms}}`.
+const ICU_MESSAGE_TYPES: [&str; 3] = ["plural", "select", "selectordinal"];
+
+/// Generates pseudo-locale from reference. Every text is accented, padded by
+/// `padding` percent of its length and wrapped in brackets, e.g. `Rooms`
+/// becomes `[Ŕööɱš~~]`. Placeholders, ICU arguments, HTML tags and entities
+/// are kept, vue-i18n pipe separated forms are wrapped one by one.
+pub fn pseudo_localize(reference: &Value, padding: u8) -> Value {
+    let placeholder = Regex::new(PLACEHOLDER).unwrap();
+    pseudo_value(&placeholder, reference, padding)
+}
+
+fn pseudo_value(placeholder: &Regex, value: &Value, padding: u8) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), pseudo_value(placeholder, value, padding)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|value| pseudo_value(placeholder, value, padding))
+                .collect(),
+        ),
+        Value::String(text) => Value::String(
+            split_top_level(text, '|')
+                .into_iter()
+                .map(|form| pseudo_form(placeholder, form, padding))
+                .collect::<Vec<_>>()
+                .join("|"),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Wraps one form of text in brackets, leaving surrounding whitespace out.
+fn pseudo_form(placeholder: &Regex, form: &str, padding: u8) -> String {
+    let trimmed = form.trim();
+    if trimmed.is_empty() {
+        return form.to_string();
+    }
+    let start = form.len() - form.trim_start().len();
+    let end = start + trimmed.len();
+
+    let mut accented = String::new();
+    let visible = accent_message(placeholder, trimmed, &mut accented);
+    let padding = (visible * padding as usize).div_ceil(100);
+
+    format!(
+        "{}[{}{}]{}",
+        &form[..start],
+        accented,
+        "~".repeat(padding),
+        &form[end..]
+    )
+}
+
+/// Writes accented message to `out`. Returns number of characters of text,
+/// not counting kept placeholders and markup.
+fn accent_message(placeholder: &Regex, message: &str, out: &mut String) -> usize {
+    let mut visible = 0;
+    let mut rest = message;
+
+    while let Some(c) = rest.chars().next() {
+        let kept = match c {
+            '{' | '%' => placeholder
+                .find(rest)
+                .filter(|found| found.start() == 0)
+                .map(|found| found.end()),
+            '<' => rest.find('>').map(|end| end + 1),
+            '&' => rest
+                .find(';')
+                .filter(|&end| {
+                    end > 1
+                        && rest[1..end]
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '#')
+                })
+                .map(|end| end + 1),
+            _ => None,
+        };
+        if let Some(end) = kept {
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        if c == '{'
+            && let Some(end) = closing_brace(rest)
+        {
+            visible += accent_argument(placeholder, &rest[1..end], out);
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        out.push(accent(c));
+        visible += 1;
+        rest = &rest[c.len_utf8()..];
+    }
+    visible
+}
+
+/// Writes ICU argument (without outer braces) to `out`, accenting only
+/// messages of plural and select options.
+fn accent_argument(placeholder: &Regex, argument: &str, out: &mut String) -> usize {
+    let parts: Vec<&str> = argument.splitn(3, ',').collect();
+    let [name, kind, options] = parts[..] else {
+        out.push_str(&format!("{{{}}}", argument));
+        return 0;
+    };
+    if !ICU_MESSAGE_TYPES.contains(&kind.trim()) {
+        out.push_str(&format!("{{{}}}", argument));
+        return 0;
+    }
+
+    let mut visible = 0;
+    out.push_str(&format!("{{{},{},", name, kind));
+    let mut rest = options;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = closing_brace(&rest[start..]).map(|end| start + end) else {
+            break;
+        };
+        out.push_str(&rest[..=start]);
+        visible += accent_message(placeholder, &rest[start + 1..end], out);
+        out.push('}');
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out.push('}');
+    visible
+}
+
+/// Index of brace closing the one text starts with.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits text at separators outside of braces.
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn accent(c: char) -> char {
+    match PLAIN.find(c) {
+        Some(i) => ACCENTED.chars().nth(i).unwrap(),
+        None => c,
+    }
+}
+
+#[cfg(test)]
+mod pseudo_localize {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_accent_pad_and_wrap_every_text() {
+        let reference = json!({ "pax": { "rooms": "Rooms", "count": 2 }, "empty": "" });
+
+        let result = pseudo_localize(&reference, 30);
+
+        assert_eq!(
+            result,
+            json!({ "pax": { "rooms": "[Ŕööɱš~~]", "count": 2 }, "empty": "" })
+        );
+    }
+
+    #[test]
+    fn should_keep_placeholders_html_and_entities() {
+        let reference = json!("Hi {name}, <b>%s</b> left &amp; {{ count }}");
+
+        let result = pseudo_localize(&reference, 0);
+
+        assert_eq!(
+            result,
+            json!("[Ĥî {name}, <b>%s</b> ļéƒţ &amp; {{ count }}]")
+        );
+    }
+
+    #[test]
+    fn should_accent_only_messages_of_icu_arguments() {
+        let reference = json!("{count, plural, one {# room} other {# rooms}}");
+
+        let result = pseudo_localize(&reference, 0);
+
+        assert_eq!(
+            result,
+            json!("[{count, plural, one {# ŕööɱ} other {# ŕööɱš}}]")
+        );
+    }
+
+    #[test]
+    fn should_wrap_every_form_separated_by_pipe() {
+        let reference = json!("no rooms | {n} room | {n} rooms");
+
+        let result = pseudo_localize(&reference, 0);
+
+        assert_eq!(result, json!("[ñö ŕööɱš] | [{n} ŕööɱ] | [{n} ŕööɱš]"));
+    }
+}
@@ -1,13 +1,26 @@
+use crate::json::key_path::KeyPath;
 use grep::regex::RegexMatcher;
 use grep::searcher::{Searcher, sinks::UTF8};
 use ignore::WalkBuilder;
-use regex;
 
-pub fn find_unused_paths(patterns: Vec<String>, dir: String) -> Result<Vec<String>, String> {
+/// Whether text uses key written with plain dots, e.g. `t('pax.rooms')`. The
+/// key has to end where an identifier would, so `pax.rooms` isn't found in
+/// `pax.roomsCount`.
+pub fn mentions_key(text: &str, key: &KeyPath) -> bool {
+    let dotted = key.to_dotted();
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    text.match_indices(&dotted).any(|(start, found)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + found.len()..].chars().next();
+        !before.is_some_and(is_identifier) && !after.is_some_and(is_identifier)
+    })
+}
+
+pub fn find_unused_paths(patterns: Vec<KeyPath>, dir: String) -> Result<Vec<KeyPath>, String> {
     let mut unused = Vec::new();
 
     for pattern in patterns {
-        let matcher = RegexMatcher::new(&regex::escape(&pattern))
+        let matcher = RegexMatcher::new(&regex::escape(&pattern.to_dotted()))
             .map_err(|e| format!("Failed to create regex matcher: {}", e))?;
         let mut found = false;
 
@@ -53,3 +66,17 @@ pub fn find_unused_paths(patterns: Vec<String>, dir: String) -> Result<Vec<Strin
 
     Ok(unused)
 }
+
+#[cfg(test)]
+mod mentions_key {
+    use super::*;
+
+    #[test]
+    fn should_find_key_ending_at_identifier_boundary() {
+        let key = KeyPath::parse("pax.rooms").unwrap();
+
+        assert!(mentions_key("t('pax.rooms')", &key));
+        assert!(!mentions_key("t('pax.roomsCount')", &key));
+        assert!(!mentions_key("t('xpax.rooms')", &key));
+    }
+}
@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::{Arc, mpsc};
+use std::thread;
+
+use serde_json::{Value, json};
+use tiny_http::{Header, Response, Server};
+
+use crate::json::key_path::KeyPath;
+use crate::{Catalog, Error};
+
+const EDITOR: &str = include_str!("serve.html");
+
+/// Threads reading requests. Calls are still answered one at a time by the
+/// thread owning the catalog.
+const WORKERS: usize = 4;
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY: u64 = 1024 * 1024;
+
+/// Request to the catalog, parsed from method and URL.
+#[derive(Debug, PartialEq)]
+enum Call {
+    ListLocales,
+    ListKeys,
+    GetKey(KeyPath),
+    SetValue {
+        key: KeyPath,
+        locale: String,
+        value: Value,
+    },
+    AddKey {
+        key: KeyPath,
+        values: BTreeMap<String, Value>,
+    },
+    RemoveKey(KeyPath),
+    Compare,
+}
+
+#[derive(Debug, PartialEq)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        let status = match &error {
+            Error::InvalidKey(_) => 400,
+            Error::LocaleNotFound(_) | Error::KeyNotFound(_) => 404,
+            Error::Conflicts(_) => 409,
+            _ => 500,
+        };
+        ApiError::new(status, error.to_string())
+    }
+}
+
+type Reply = Result<Value, ApiError>;
+
+/// Answers call using catalog. Called by a single thread owning the catalog,
+/// so writes never interleave.
+fn handle(catalog: &mut Catalog, call: Call) -> Reply {
+    match call {
+        Call::ListLocales => Ok(json!(catalog.locales().collect::<Vec<_>>())),
+        Call::ListKeys => Ok(Value::Array(
+            catalog
+                .paths()
+                .iter()
+                .map(|key| json!({ "key": key.to_string(), "values": values(catalog, key) }))
+                .collect(),
+        )),
+        Call::GetKey(key) => {
+            let values = values(catalog, &key);
+            if values.values().all(Value::is_null) {
+                return Err(Error::KeyNotFound(key).into());
+            }
+            Ok(json!(values))
+        }
+        Call::SetValue { key, locale, value } => {
+            let result = catalog.set(&locale, &key, value)?;
+            Ok(json!({ "result": result.to_string() }))
+        }
+        Call::AddKey { key, values: given } => {
+            // Keys added through the editor must show up in every locale.
+            let mut values: BTreeMap<String, Value> = catalog
+                .locales()
+                .map(|locale| (locale.to_string(), json!("")))
+                .collect();
+            for (locale, value) in given {
+                if !values.contains_key(&locale) {
+                    return Err(Error::LocaleNotFound(locale).into());
+                }
+                values.insert(locale, value);
+            }
+            if catalog.node_paths().contains(&key) {
+                return Err(ApiError::new(409, format!("Key '{}' already exists!", key)));
+            }
+
+            catalog.add(&key, values)?;
+            Ok(json!(self::values(catalog, &key)))
+        }
+        Call::RemoveKey(key) => {
+            let removed = catalog.remove(&key)?;
+            if removed.is_empty() {
+                return Err(Error::KeyNotFound(key).into());
+            }
+            Ok(json!({ "removed": removed }))
+        }
+        Call::Compare => {
+            let Ok(reference) = catalog.reference(None) else {
+                return Ok(json!({ "reference": null, "locales": [] }));
+            };
+            let others = catalog.locales().filter(|locale| *locale != reference);
+            let locales: Vec<Value> = catalog
+                .compare(reference)?
+                .into_iter()
+                .zip(others)
+                .map(|(diff, locale)| {
+                    json!({
+                        "locale": locale,
+                        "missing_in_target": diff
+                            .missing_in_target
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>(),
+                        "missing_in_reference": diff
+                            .missing_in_reference
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>(),
+                        "shape_conflicts": diff
+                            .shape_conflicts
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            Ok(json!({ "reference": reference, "locales": locales }))
+        }
+    }
+}
+
+/// Value of key in every locale, `null` where it's missing.
+fn values<'a>(catalog: &'a Catalog, key: &KeyPath) -> BTreeMap<&'a str, Value> {
+    catalog
+        .locales()
+        .map(|locale| {
+            let value = catalog.get(locale, key).ok().flatten().cloned();
+            (locale, value.unwrap_or(Value::Null))
+        })
+        .collect()
+}
+
+/// Serves the web editor and the REST API for every file in directory.
+pub fn run(where_: &str, port: u16, token: Option<String>) -> Result<(), String> {
+    let mut catalog = Catalog::open(where_).map_err(|e| e.to_string())?;
+
+    let server = Arc::new(
+        Server::http(("127.0.0.1", port))
+            .map_err(|e| format!("Can't listen on {}: {}", port, e))?,
+    );
+    println!("Serving {} on http://127.0.0.1:{}/", where_, port);
+
+    let (sender, receiver) = mpsc::channel::<(Call, mpsc::Sender<Reply>)>();
+    for _ in 0..WORKERS {
+        let server = Arc::clone(&server);
+        let sender = sender.clone();
+        let token = token.clone();
+        thread::spawn(move || {
+            while let Ok(request) = server.recv() {
+                respond(request, &sender, token.as_deref());
+            }
+        });
+    }
+    drop(sender);
+
+    for (call, reply) in receiver {
+        let mut result = handle(&mut catalog, call);
+        if let Err(e) = catalog.save() {
+            result = Err(e.into());
+        }
+        let _ = reply.send(result);
+    }
+
+    Ok(())
+}
+
+fn respond(
+    mut request: tiny_http::Request,
+    catalog: &mpsc::Sender<(Call, mpsc::Sender<Reply>)>,
+    token: Option<&str>,
+) {
+    let method = request.method().as_str().to_string();
+    let url = request.url().to_string();
+
+    if method == "GET" && (url == "/" || url.starts_with("/?")) {
+        let _ =
+            request.respond(Response::from_string(EDITOR).with_header(content_type("text/html")));
+        return;
+    }
+
+    let reply = (|| {
+        if let Some(token) = token {
+            let authorization = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("Authorization"))
+                .map(|header| header.value.as_str());
+            if authorization != Some(format!("Bearer {}", token).as_str()) {
+                return Err(ApiError::new(401, "Missing or invalid token!"));
+            }
+        }
+
+        let mut body = String::new();
+        request
+            .as_reader()
+            .take(MAX_BODY)
+            .read_to_string(&mut body)
+            .map_err(|e| ApiError::new(400, e.to_string()))?;
+        let call = route(&method, &url, &body)?;
+
+        let (reply_sender, reply_receiver) = mpsc::channel();
+        catalog
+            .send((call, reply_sender))
+            .map_err(|_| ApiError::new(503, "Catalog is not available!"))?;
+        reply_receiver
+            .recv()
+            .map_err(|_| ApiError::new(503, "Catalog is not available!"))?
+    })();
+
+    let (status, body) = match reply {
+        Ok(value) => (200, value),
+        Err(error) => (error.status, json!({ "error": error.message })),
+    };
+    let _ = request.respond(
+        Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type("application/json")),
+    );
+}
+
+/// Maps method and URL to a catalog call. Keys are percent-encoded in URL,
+/// nested keys use dotted notation.
+fn route(method: &str, url: &str, body: &str) -> Result<Call, ApiError> {
+    let path = url.split('?').next().unwrap_or_default();
+    let segments: Vec<String> = path
+        .trim_matches('/')
+        .split('/')
+        .map(percent_decode)
+        .collect::<Result<_, _>>()?;
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let parse_key = |key: &str| KeyPath::parse(key).map_err(|e| ApiError::new(400, e.to_string()));
+    let parse_body = || {
+        serde_json::from_str::<Value>(body)
+            .map_err(|e| ApiError::new(400, format!("Invalid JSON body: {}", e)))
+    };
+
+    match (method, segments.as_slice()) {
+        ("GET", ["api", "locales"]) => Ok(Call::ListLocales),
+        ("GET", ["api", "keys"]) => Ok(Call::ListKeys),
+        ("POST", ["api", "keys"]) => {
+            let body = parse_body()?;
+            let key = body
+                .get("key")
+                .and_then(Value::as_str)
+                .ok_or_else(|| ApiError::new(400, "Body must contain 'key'!"))?;
+            let values = match body.get("values") {
+                None => BTreeMap::new(),
+                Some(values) => serde_json::from_value(values.clone())
+                    .map_err(|_| ApiError::new(400, "'values' must be an object!"))?,
+            };
+            Ok(Call::AddKey {
+                key: parse_key(key)?,
+                values,
+            })
+        }
+        ("GET", ["api", "keys", key]) => Ok(Call::GetKey(parse_key(key)?)),
+        ("DELETE", ["api", "keys", key]) => Ok(Call::RemoveKey(parse_key(key)?)),
+        ("PUT", ["api", "keys", key, locale]) => Ok(Call::SetValue {
+            key: parse_key(key)?,
+            locale: locale.to_string(),
+            value: parse_body()?,
+        }),
+        ("GET", ["api", "compare"]) => Ok(Call::Compare),
+        (_, ["api", "locales" | "keys" | "compare", ..]) => {
+            Err(ApiError::new(405, "Method not allowed!"))
+        }
+        _ => Err(ApiError::new(404, "Not found!")),
+    }
+}
+
+fn percent_decode(segment: &str) -> Result<String, ApiError> {
+    let invalid = || ApiError::new(400, format!("Invalid percent-encoding in '{}'!", segment));
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = segment.get(i + 1..i + 3).ok_or_else(invalid)?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", format!("{}; charset=utf-8", value)).unwrap()
+}
+
+#[cfg(test)]
+mod route {
+    use super::*;
+
+    #[test]
+    fn should_decode_key_and_locale() {
+        let result = route("PUT", "/api/keys/pax.rooms%20count/sv", "\"Rum\"");
+
+        assert_eq!(
+            result,
+            Ok(Call::SetValue {
+                key: KeyPath::parse("pax.rooms count").unwrap(),
+                locale: "sv".to_string(),
+                value: json!("Rum"),
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_added_key() {
+        let result = route(
+            "POST",
+            "/api/keys",
+            r#"{ "key": "pax.rooms", "values": { "en": "Rooms" } }"#,
+        );
+
+        assert_eq!(
+            result,
+            Ok(Call::AddKey {
+                key: KeyPath::parse("pax.rooms").unwrap(),
+                values: BTreeMap::from([("en".to_string(), json!("Rooms"))]),
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_routes() {
+        assert_eq!(route("GET", "/api/nope", "").unwrap_err().status, 404);
+        assert_eq!(route("POST", "/api/compare", "").unwrap_err().status, 405);
+        assert_eq!(route("PUT", "/api/keys/a/en", "{").unwrap_err().status, 400);
+    }
+}
+
+#[cfg(test)]
+mod handle {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn should_list_keys_with_values_per_locale() {
+        let result = handle(&mut catalog(), Call::ListKeys);
+
+        assert_eq!(
+            result,
+            Ok(json!([
+                { "key": "pax.adults", "values": { "en": "Adults", "sv": null } },
+                { "key": "pax.rooms", "values": { "en": "Rooms", "sv": "Rum" } },
+            ]))
+        );
+    }
+
+    #[test]
+    fn should_set_value_in_one_locale() {
+        let mut catalog = catalog();
+
+        let result = handle(
+            &mut catalog,
+            Call::SetValue {
+                key: key("pax.adults"),
+                locale: "sv".to_string(),
+                value: json!("Vuxna"),
+            },
+        );
+
+        assert_eq!(result, Ok(json!({ "result": "created" })));
+        assert_eq!(
+            catalog.json("sv").unwrap(),
+            &json!({ "pax": { "rooms": "Rum", "adults": "Vuxna" } })
+        );
+    }
+
+    #[test]
+    fn should_add_key_to_every_locale() {
+        let result = handle(
+            &mut catalog(),
+            Call::AddKey {
+                key: key("pax.children"),
+                values: BTreeMap::from([("en".to_string(), json!("Children"))]),
+            },
+        );
+
+        assert_eq!(result, Ok(json!({ "en": "Children", "sv": "" })));
+    }
+
+    #[test]
+    fn should_not_add_existing_key() {
+        let mut catalog = catalog();
+
+        let result = handle(
+            &mut catalog,
+            Call::AddKey {
+                key: key("pax.adults"),
+                values: BTreeMap::new(),
+            },
+        );
+
+        assert_eq!(result.unwrap_err().status, 409);
+        assert_eq!(catalog.get("sv", &key("pax.adults")).unwrap(), None);
+    }
+
+    #[test]
+    fn should_remove_key_where_present() {
+        let mut catalog = catalog();
+
+        let result = handle(&mut catalog, Call::RemoveKey(key("pax.adults")));
+
+        assert_eq!(result, Ok(json!({ "removed": ["en"] })));
+        assert_eq!(
+            handle(&mut catalog, Call::GetKey(key("pax.adults")))
+                .unwrap_err()
+                .status,
+            404
+        );
+    }
+
+    #[test]
+    fn should_compare_locales_with_first_one() {
+        let result = handle(&mut catalog(), Call::Compare);
+
+        assert_eq!(
+            result,
+            Ok(json!({
+                "reference": "en",
+                "locales": [
+                    { "locale": "sv", "missing_in_target": ["pax.adults"], "missing_in_reference": [], "shape_conflicts": [] }
+                ]
+            }))
+        );
+    }
+}
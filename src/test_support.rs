@@ -0,0 +1,50 @@
+//! Fixtures shared by unit tests of several modules.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::{Value, json};
+
+use crate::check::Problem;
+use crate::{Catalog, KeyPath};
+
+pub fn key(key: &str) -> KeyPath {
+    KeyPath::parse(key).unwrap()
+}
+
+/// English and Swedish locale files in `lang/`, Swedish listed first.
+pub fn locales(en: Value, sv: Value) -> Vec<(PathBuf, Value)> {
+    vec![
+        (PathBuf::from("lang/sv.json"), sv),
+        (PathBuf::from("lang/en.json"), en),
+    ]
+}
+
+/// Catalog where Swedish lacks `pax.adults`.
+pub fn catalog() -> Catalog {
+    Catalog::new(locales(
+        json!({ "pax": { "rooms": "Rooms", "adults": "Adults" } }),
+        json!({ "pax": { "rooms": "Rum" } }),
+    ))
+}
+
+pub fn messages(problems: Vec<Problem>) -> Vec<String> {
+    problems.iter().map(ToString::to_string).collect()
+}
+
+/// Empty directory for files written by a test, unique to the test name.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("szambo-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Directory with given locale files, unique to the test name.
+pub fn locale_dir(name: &str, locales: &[(&str, Value)]) -> PathBuf {
+    let dir = temp_dir(name);
+    for (file, json) in locales {
+        crate::files::save_value_to_json_file(json, &dir.join(file)).unwrap();
+    }
+    dir
+}
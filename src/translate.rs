@@ -0,0 +1,313 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::check::PLACEHOLDER;
+
+/// Machine translation service.
+pub trait Provider {
+    /// Translates texts between locales, e.g. `en` and `sv`. Returns
+    /// translations in the same order.
+    fn translate(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>, String>;
+}
+
+/// Shape of request and response of an HTTP translation API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpApi {
+    /// `{"text": [...], "source_lang": "EN", "target_lang": "SV"}`, answered
+    /// with `{"translations": [{"text": ...}]}`.
+    Deepl,
+    /// `{"q": [...], "source": "en", "target": "sv"}`, answered with
+    /// `{"translatedText": [...]}`.
+    LibreTranslate,
+}
+
+/// Posts texts as JSON to an HTTP endpoint, e.g.
+/// `https://api-free.deepl.com/v2/translate`.
+pub struct HttpProvider {
+    pub endpoint: String,
+    pub api: HttpApi,
+    pub api_key: Option<String>,
+}
+
+impl Provider for HttpProvider {
+    fn translate(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>, String> {
+        let mut request = ureq::post(&self.endpoint);
+        let body = match self.api {
+            HttpApi::Deepl => {
+                if let Some(api_key) = &self.api_key {
+                    request =
+                        request.header("Authorization", format!("DeepL-Auth-Key {}", api_key));
+                }
+                json!({
+                    "text": texts,
+                    "source_lang": from.to_uppercase(),
+                    "target_lang": to.to_uppercase(),
+                })
+            }
+            HttpApi::LibreTranslate => {
+                let mut body =
+                    json!({ "q": texts, "source": from, "target": to, "format": "text" });
+                if let Some(api_key) = &self.api_key {
+                    body["api_key"] = json!(api_key);
+                }
+                body
+            }
+        };
+
+        let response: Value = request
+            .send_json(&body)
+            .and_then(|mut response| response.body_mut().read_json())
+            .map_err(|e| format!("Request to {} failed: {}", self.endpoint, e))?;
+
+        let translations = match self.api {
+            HttpApi::Deepl => response["translations"]
+                .as_array()
+                .map(|items| items.iter().map(|item| &item["text"]).collect::<Vec<_>>()),
+            HttpApi::LibreTranslate => response["translatedText"]
+                .as_array()
+                .map(|items| items.iter().collect()),
+        };
+        translations
+            .and_then(|items| {
+                items
+                    .into_iter()
+                    .map(|text| text.as_str().map(str::to_string))
+                    .collect()
+            })
+            .ok_or_else(|| format!("Unexpected response from {}: {}", self.endpoint, response))
+    }
+}
+
+/// Runs a shell command with `{"from": "en", "to": "sv", "texts": [...]}` on
+/// its standard input. The command prints a JSON array of translations.
+pub struct CommandProvider {
+    pub command: String,
+}
+
+impl Provider for CommandProvider {
+    fn translate(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>, String> {
+        let mut child = Command::new("sh")
+            .args(["-c", &self.command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Can't run '{}': {}", self.command, e))?;
+
+        let input = json!({ "from": from, "to": to, "texts": texts });
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.to_string().as_bytes())
+            .map_err(|e| format!("Can't write to '{}': {}", self.command, e))?;
+
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(format!("'{}' failed with {}", self.command, output.status));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Unexpected output of '{}': {}", self.command, e))
+    }
+}
+
+/// Most texts sent to provider in one call, APIs limit size of a request.
+const BATCH_SIZE: usize = 50;
+
+/// Translates texts with one call to provider per [`BATCH_SIZE`] texts.
+/// Placeholders are replaced with tokens like `__PH0__` before the call and
+/// restored afterwards. A text whose translation lost a token gets an error
+/// instead.
+pub fn translate_texts(
+    provider: &dyn Provider,
+    texts: &[String],
+    from: &str,
+    to: &str,
+) -> Result<Vec<Result<String, String>>, String> {
+    let placeholder = Regex::new(PLACEHOLDER).unwrap();
+    let (masked, placeholders): (Vec<String>, Vec<Vec<String>>) = texts
+        .iter()
+        .map(|text| mask_placeholders(&placeholder, text))
+        .unzip();
+
+    let mut translated = Vec::with_capacity(texts.len());
+    for batch in masked.chunks(BATCH_SIZE) {
+        let batch_translated = provider.translate(batch, from, to)?;
+        if batch_translated.len() != batch.len() {
+            return Err(format!(
+                "Provider returned {} translations for {} texts!",
+                batch_translated.len(),
+                batch.len()
+            ));
+        }
+        translated.extend(batch_translated);
+    }
+
+    Ok(translated
+        .iter()
+        .zip(&placeholders)
+        .map(|(text, placeholders)| unmask_placeholders(text, placeholders))
+        .collect())
+}
+
+fn mask_placeholders(placeholder: &Regex, text: &str) -> (String, Vec<String>) {
+    let mut placeholders = Vec::new();
+    let masked = placeholder.replace_all(text, |found: &regex::Captures| {
+        placeholders.push(found[0].to_string());
+        format!("__PH{}__", placeholders.len() - 1)
+    });
+    (masked.into_owned(), placeholders)
+}
+
+fn unmask_placeholders(text: &str, placeholders: &[String]) -> Result<String, String> {
+    let mut text = text.to_string();
+    for (i, placeholder) in placeholders.iter().enumerate() {
+        let token = format!("__PH{}__", i);
+        if !text.contains(&token) {
+            return Err(format!("Placeholder '{}' lost in translation", placeholder));
+        }
+        text = text.replacen(&token, placeholder, 1);
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod translate_texts {
+    use super::*;
+
+    /// Translates `rooms` only, loses tokens of texts starting with `drop`.
+    struct Shouting;
+
+    impl Provider for Shouting {
+        fn translate(&self, texts: &[String], _: &str, _: &str) -> Result<Vec<String>, String> {
+            Ok(texts
+                .iter()
+                .map(|text| match text.strip_prefix("drop") {
+                    Some(_) => "DROPPED".to_string(),
+                    None => text.replace("rooms", "ROOMS"),
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn should_keep_placeholders_untouched_by_provider() {
+        let texts = vec!["{count} rooms for %{name}".to_string()];
+
+        let result = translate_texts(&Shouting, &texts, "en", "sv").unwrap();
+
+        assert_eq!(result, vec![Ok("{count} ROOMS for %{name}".to_string())]);
+    }
+
+    #[test]
+    fn should_report_text_whose_placeholder_was_lost() {
+        let texts = vec!["drop {count}".to_string(), "rooms".to_string()];
+
+        let result = translate_texts(&Shouting, &texts, "en", "sv").unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                Err("Placeholder '{count}' lost in translation".to_string()),
+                Ok("ROOMS".to_string())
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod http_provider {
+    use super::*;
+    use std::thread;
+
+    /// Answers given number of requests with response made from request
+    /// body, returns bodies of the requests.
+    fn mock_server(
+        requests: usize,
+        response: fn(&Value) -> Value,
+    ) -> (String, thread::JoinHandle<Vec<Value>>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/translate", server.server_addr());
+        let handle = thread::spawn(move || {
+            (0..requests)
+                .map(|_| {
+                    let mut request = server.recv().unwrap();
+                    let mut body = String::new();
+                    request.as_reader().read_to_string(&mut body).unwrap();
+                    let body: Value = serde_json::from_str(&body).unwrap();
+                    request
+                        .respond(tiny_http::Response::from_string(
+                            response(&body).to_string(),
+                        ))
+                        .unwrap();
+                    body
+                })
+                .collect()
+        });
+        (endpoint, handle)
+    }
+
+    #[test]
+    fn should_post_texts_in_deepl_shape() {
+        let (endpoint, server) = mock_server(1, |_| json!({ "translations": [{ "text": "Rum" }] }));
+        let provider = HttpProvider {
+            endpoint,
+            api: HttpApi::Deepl,
+            api_key: None,
+        };
+
+        let result = provider.translate(&["Rooms".to_string()], "en", "sv");
+
+        assert_eq!(result, Ok(vec!["Rum".to_string()]));
+        assert_eq!(
+            server.join().unwrap(),
+            vec![json!({ "text": ["Rooms"], "source_lang": "EN", "target_lang": "SV" })]
+        );
+    }
+
+    #[test]
+    fn should_read_libretranslate_response() {
+        let (endpoint, server) = mock_server(1, |_| json!({ "translatedText": ["Rum", "Vuxna"] }));
+        let provider = HttpProvider {
+            endpoint,
+            api: HttpApi::LibreTranslate,
+            api_key: Some("secret".to_string()),
+        };
+
+        let result = provider.translate(&["Rooms".to_string(), "Adults".to_string()], "en", "sv");
+
+        assert_eq!(result, Ok(vec!["Rum".to_string(), "Vuxna".to_string()]));
+        assert_eq!(server.join().unwrap()[0]["api_key"], json!("secret"));
+    }
+
+    #[test]
+    fn should_send_texts_in_batches() {
+        let (endpoint, server) = mock_server(3, |body| {
+            let texts = body["text"].as_array().unwrap();
+            json!({ "translations": texts
+                .iter()
+                .map(|text| json!({ "text": text.as_str().unwrap().to_uppercase() }))
+                .collect::<Vec<_>>() })
+        });
+        let provider = HttpProvider {
+            endpoint,
+            api: HttpApi::Deepl,
+            api_key: None,
+        };
+        let texts: Vec<String> = (0..120).map(|i| format!("room {}", i)).collect();
+
+        let result = translate_texts(&provider, &texts, "en", "sv").unwrap();
+
+        assert_eq!(result.len(), 120);
+        assert_eq!(result[119], Ok("ROOM 119".to_string()));
+        let batches: Vec<usize> = server
+            .join()
+            .unwrap()
+            .iter()
+            .map(|body| body["text"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(batches, vec![50, 50, 20]);
+    }
+}
@@ -0,0 +1,591 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Cell as TableCell, Paragraph, Row, Table, TableState};
+use serde_json::Value;
+
+use crate::Catalog;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::ensure_path_is_free;
+use crate::json::rename::ConflictStrategy;
+
+const HELP: &str = "↑↓←→ move  enter edit  a add  r rename  d delete  f filter  s save  q quit";
+
+/// Row of the key tree. Objects are shown as headers of their keys.
+#[derive(Debug, PartialEq)]
+struct KeyRow {
+    path: KeyPath,
+    is_value: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Cell<'a> {
+    Missing,
+    /// Empty, or same as in the reference locale.
+    Untranslated(&'a Value),
+    Translated(&'a Value),
+}
+
+/// Locale files being edited, independent of the terminal.
+struct Editor {
+    catalog: Catalog,
+    /// Locales in column order.
+    locales: Vec<String>,
+    /// Locale untranslated values are compared with, see [`Catalog::reference`].
+    reference: usize,
+    /// Show only keys missing or untranslated in this locale.
+    filter: Option<usize>,
+    rows: Vec<KeyRow>,
+}
+
+impl Editor {
+    fn new(catalog: Catalog) -> Self {
+        let mut editor = Editor {
+            locales: catalog.locales().map(str::to_string).collect(),
+            catalog,
+            reference: 0,
+            filter: None,
+            rows: Vec::new(),
+        };
+        editor.refresh_rows();
+        editor
+    }
+
+    fn refresh_rows(&mut self) {
+        // Edits may change which locale has the most keys.
+        self.reference = self
+            .catalog
+            .reference(None)
+            .ok()
+            .and_then(|reference| self.locales.iter().position(|locale| locale == reference))
+            .unwrap_or(0);
+        let keys: BTreeSet<KeyPath> = self
+            .catalog
+            .paths()
+            .into_iter()
+            .filter(|path| match self.filter {
+                Some(locale) => !matches!(self.cell(path, locale), Cell::Translated(_)),
+                None => true,
+            })
+            .collect();
+
+        let mut rows = BTreeSet::new();
+        for key in &keys {
+            let segments = key.segments();
+            for depth in 1..segments.len() {
+                let parent = segments[..depth]
+                    .iter()
+                    .fold(KeyPath::default(), |parent, segment| parent.child(segment));
+                rows.insert(parent);
+            }
+            rows.insert(key.clone());
+        }
+
+        self.rows = rows
+            .into_iter()
+            .map(|path| KeyRow {
+                is_value: keys.contains(&path),
+                path,
+            })
+            .collect();
+    }
+
+    fn cell(&self, path: &KeyPath, locale: usize) -> Cell<'_> {
+        let Some(value) = self.get(path, locale) else {
+            return Cell::Missing;
+        };
+        let reference = self.get(path, self.reference);
+
+        if value.as_str() == Some("") || (locale != self.reference && reference == Some(value)) {
+            Cell::Untranslated(value)
+        } else {
+            Cell::Translated(value)
+        }
+    }
+
+    fn get(&self, path: &KeyPath, locale: usize) -> Option<&Value> {
+        self.catalog.get(&self.locales[locale], path).ok().flatten()
+    }
+
+    fn file_name(&self, locale: usize) -> String {
+        self.catalog
+            .file(&self.locales[locale])
+            .ok()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn is_changed(&self) -> bool {
+        self.catalog.changed().next().is_some()
+    }
+
+    /// Sets value of key in locale. Text replaces strings as is, other
+    /// values have to be entered as JSON.
+    fn set_value(&mut self, path: &KeyPath, locale: usize, input: &str) -> Result<(), String> {
+        let value = match self.get(path, locale) {
+            Some(Value::String(_)) | None => Value::from(input),
+            Some(_) => serde_json::from_str(input)
+                .map_err(|_| format!("Value at '{}' isn't text, enter valid JSON!", path))?,
+        };
+        self.catalog
+            .set(&self.locales[locale], path, value)
+            .map_err(|e| e.to_string())?;
+
+        self.refresh_rows();
+        Ok(())
+    }
+
+    /// Adds key with an empty value to every locale.
+    fn add_key(&mut self, path: &KeyPath) -> Result<(), String> {
+        for locale in &self.locales {
+            let json = self.catalog.json(locale).map_err(|e| e.to_string())?;
+            ensure_path_is_free(json, path)?;
+        }
+        let values = self
+            .locales
+            .iter()
+            .map(|locale| (locale.clone(), Value::from("")));
+        self.catalog.add(path, values).map_err(|e| e.to_string())?;
+
+        self.refresh_rows();
+        Ok(())
+    }
+
+    /// Renames key in every locale having it. No locale is changed if any
+    /// of them fails.
+    fn rename_key(&mut self, from: &KeyPath, to: &KeyPath) -> Result<(), String> {
+        self.catalog
+            .rename(from, to, ConflictStrategy::Fail)
+            .map_err(|e| e.to_string())?;
+
+        self.refresh_rows();
+        Ok(())
+    }
+
+    fn delete_key(&mut self, path: &KeyPath) -> Result<(), String> {
+        self.catalog.remove(path).map_err(|e| e.to_string())?;
+
+        self.refresh_rows();
+        Ok(())
+    }
+
+    fn cycle_filter(&mut self) {
+        self.filter = match self.filter {
+            None => Some(0),
+            Some(locale) if locale + 1 < self.locales.len() => Some(locale + 1),
+            Some(_) => None,
+        };
+        self.refresh_rows();
+    }
+
+    /// Writes changed locales and returns how many there were.
+    fn save(&mut self) -> Result<usize, String> {
+        let count = self.catalog.changed().count();
+        self.catalog.save().map_err(|e| e.to_string())?;
+        Ok(count)
+    }
+}
+
+enum Prompt {
+    Edit(KeyPath, usize),
+    Add,
+    Rename(KeyPath),
+}
+
+enum Mode {
+    Normal,
+    Input(Prompt, String),
+    ConfirmDelete(KeyPath),
+    ConfirmQuit,
+}
+
+struct App {
+    editor: Editor,
+    mode: Mode,
+    status: String,
+    row: usize,
+    /// Locale index of the selected column.
+    column: usize,
+}
+
+/// Opens every file in directory in the terminal editor.
+pub fn run(where_: &str) -> Result<(), String> {
+    let catalog = Catalog::open(where_).map_err(|e| e.to_string())?;
+    if catalog.locales().next().is_none() {
+        return Err(format!("No JSON files found in {}", where_));
+    }
+
+    let mut app = App {
+        editor: Editor::new(catalog),
+        mode: Mode::Normal,
+        status: HELP.to_string(),
+        row: 0,
+        column: 0,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
+        loop {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .map_err(|e| e.to_string())?;
+
+            let Event::Key(key) = event::read().map_err(|e| e.to_string())? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let mode = std::mem::replace(&mut self.mode, Mode::Normal);
+            let quit = match mode {
+                Mode::Normal => self.handle_normal(key.code),
+                Mode::Input(prompt, buffer) => {
+                    self.handle_input(prompt, buffer, key.code);
+                    false
+                }
+                Mode::ConfirmDelete(path) => {
+                    if key.code == KeyCode::Char('y') {
+                        let result = self.editor.delete_key(&path);
+                        self.report(result, format!("Deleted '{}'", path));
+                    } else {
+                        self.status = HELP.to_string();
+                    }
+                    false
+                }
+                Mode::ConfirmQuit => key.code == KeyCode::Char('y'),
+            };
+
+            self.row = self.row.min(self.editor.rows.len().saturating_sub(1));
+            if quit {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Returns true when the editor should close.
+    fn handle_normal(&mut self, code: KeyCode) -> bool {
+        let selected = self
+            .editor
+            .rows
+            .get(self.row)
+            .map(|row| (row.path.clone(), row.is_value));
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.row = self.row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.row += 1,
+            KeyCode::Left | KeyCode::Char('h') => self.column = self.column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.column = (self.column + 1).min(self.editor.locales.len() - 1)
+            }
+            KeyCode::Enter => match selected {
+                Some((path, true)) => {
+                    let current = match self.editor.cell(&path, self.column) {
+                        Cell::Missing => String::new(),
+                        Cell::Untranslated(value) | Cell::Translated(value) => display_value(value),
+                    };
+                    self.mode = Mode::Input(Prompt::Edit(path, self.column), current);
+                }
+                _ => self.status = "Select a key holding a value to edit it".to_string(),
+            },
+            KeyCode::Char('a') => {
+                let prefix = selected
+                    .map(|(path, is_value)| {
+                        if is_value {
+                            String::new()
+                        } else {
+                            format!("{}.", path)
+                        }
+                    })
+                    .unwrap_or_default();
+                self.mode = Mode::Input(Prompt::Add, prefix);
+            }
+            KeyCode::Char('r') => {
+                if let Some((path, _)) = selected {
+                    self.mode = Mode::Input(Prompt::Rename(path.clone()), path.to_string());
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some((path, _)) = selected {
+                    self.status = format!("Delete '{}' in every locale? (y/n)", path);
+                    self.mode = Mode::ConfirmDelete(path);
+                }
+            }
+            KeyCode::Char('f') => {
+                self.editor.cycle_filter();
+                self.status = match self.editor.filter {
+                    Some(locale) => format!(
+                        "Showing keys missing or untranslated in {}",
+                        self.editor.file_name(locale)
+                    ),
+                    None => "Showing all keys".to_string(),
+                };
+            }
+            KeyCode::Char('s') => {
+                self.status = match self.editor.save() {
+                    Ok(count) => format!("Saved {} file(s)", count),
+                    Err(error) => format!("Error: {}", error),
+                };
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if !self.editor.is_changed() {
+                    return true;
+                }
+                self.status = "Unsaved changes, quit anyway? (y/n)".to_string();
+                self.mode = Mode::ConfirmQuit;
+            }
+            _ => {}
+        }
+
+        false
+    }
+
+    fn handle_input(&mut self, prompt: Prompt, mut buffer: String, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.status = HELP.to_string();
+                return;
+            }
+            KeyCode::Enter => {}
+            KeyCode::Backspace => {
+                buffer.pop();
+                self.mode = Mode::Input(prompt, buffer);
+                return;
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                self.mode = Mode::Input(prompt, buffer);
+                return;
+            }
+            _ => {
+                self.mode = Mode::Input(prompt, buffer);
+                return;
+            }
+        }
+
+        match prompt {
+            Prompt::Edit(path, locale) => {
+                let result = self.editor.set_value(&path, locale, &buffer);
+                self.report(result, format!("Updated '{}'", path));
+            }
+            Prompt::Add => {
+                let result = KeyPath::parse(&buffer).and_then(|path| self.editor.add_key(&path));
+                self.report(result, format!("Added '{}'", buffer));
+            }
+            Prompt::Rename(from) => {
+                let result =
+                    KeyPath::parse(&buffer).and_then(|to| self.editor.rename_key(&from, &to));
+                self.report(result, format!("Renamed '{}' to '{}'", from, buffer));
+            }
+        }
+    }
+
+    fn report(&mut self, result: Result<(), String>, success: String) {
+        self.status = match result {
+            Ok(()) => success,
+            Err(error) => format!("Error: {}", error),
+        };
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [table_area, input_area, status_area] = Layout::vertical([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let editor = &self.editor;
+        let header = Row::new(
+            std::iter::once("key".to_string())
+                .chain((0..editor.locales.len()).map(|locale| editor.file_name(locale))),
+        )
+        .style(Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+
+        let rows = editor.rows.iter().map(|row| {
+            let depth = row.path.segments().len() - 1;
+            let name = row.path.segments().last().copied().unwrap_or_default();
+            let key = TableCell::from(format!("{}{}", "  ".repeat(depth), name));
+            if !row.is_value {
+                return Row::new([key.style(Style::new().add_modifier(Modifier::BOLD))]);
+            }
+
+            let cells =
+                (0..editor.locales.len()).map(|locale| match editor.cell(&row.path, locale) {
+                    Cell::Missing => {
+                        TableCell::from("(missing)").style(Style::new().fg(Color::Red))
+                    }
+                    Cell::Untranslated(value) => {
+                        TableCell::from(display_value(value)).style(Style::new().fg(Color::Yellow))
+                    }
+                    Cell::Translated(value) => TableCell::from(display_value(value)),
+                });
+            Row::new(std::iter::once(key).chain(cells))
+        });
+
+        let widths = std::iter::once(Constraint::Percentage(30))
+            .chain(editor.locales.iter().map(|_| Constraint::Fill(1)));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .cell_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = TableState::new().with_selected_cell((self.row, self.column + 1));
+        frame.render_stateful_widget(table, table_area, &mut state);
+
+        if let Mode::Input(prompt, buffer) = &self.mode {
+            let label = match prompt {
+                Prompt::Edit(path, locale) => {
+                    format!("{} [{}]", path, editor.file_name(*locale))
+                }
+                Prompt::Add => "New key".to_string(),
+                Prompt::Rename(path) => format!("Rename {}", path),
+            };
+            frame.render_widget(
+                Paragraph::new(format!("{}: {}█", label, buffer)),
+                input_area,
+            );
+        }
+        let unsaved = if editor.is_changed() {
+            "[unsaved] "
+        } else {
+            ""
+        };
+        frame.render_widget(
+            Paragraph::new(format!("{}{}", unsaved, self.status))
+                .style(Style::new().add_modifier(Modifier::DIM)),
+            status_area,
+        );
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod editor {
+    use super::*;
+    use crate::test_support::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    #[test]
+    fn should_list_objects_before_their_keys() {
+        let editor = Editor::new(Catalog::new(locales(
+            json!({ "pax": { "rooms": "Rooms" }, "foo": "Foo" }),
+            json!({}),
+        )));
+
+        let rows: Vec<(String, bool)> = editor
+            .rows
+            .iter()
+            .map(|row| (row.path.to_string(), row.is_value))
+            .collect();
+
+        assert_eq!(
+            rows,
+            vec![
+                ("foo".to_string(), true),
+                ("pax".to_string(), false),
+                ("pax.rooms".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_mark_missing_and_untranslated_cells() {
+        let editor = Editor::new(Catalog::new(locales(
+            json!({ "a": "A", "b": "B", "c": "C" }),
+            json!({ "a": "A", "b": "" }),
+        )));
+
+        assert_eq!(editor.cell(&key("a"), 0), Cell::Translated(&json!("A")));
+        assert_eq!(editor.cell(&key("a"), 1), Cell::Untranslated(&json!("A")));
+        assert_eq!(editor.cell(&key("b"), 1), Cell::Untranslated(&json!("")));
+        assert_eq!(editor.cell(&key("c"), 1), Cell::Missing);
+    }
+
+    #[test]
+    fn should_compare_with_locale_having_most_keys() {
+        let editor = Editor::new(Catalog::new([
+            (PathBuf::from("lang/de.json"), json!({ "a": "A" })),
+            (PathBuf::from("lang/en.json"), json!({ "a": "A", "b": "B" })),
+        ]));
+
+        assert_eq!(editor.cell(&key("a"), 0), Cell::Untranslated(&json!("A")));
+        assert_eq!(editor.cell(&key("a"), 1), Cell::Translated(&json!("A")));
+    }
+
+    #[test]
+    fn should_filter_keys_missing_in_locale() {
+        let mut editor = Editor::new(Catalog::new(locales(
+            json!({ "a": "A", "b": "B" }),
+            json!({ "b": "Bee" }),
+        )));
+
+        editor.cycle_filter();
+        editor.cycle_filter();
+
+        assert_eq!(editor.filter, Some(1));
+        assert_eq!(editor.rows.len(), 1);
+        assert_eq!(editor.rows[0].path, key("a"));
+    }
+
+    #[test]
+    fn should_set_missing_and_existing_values() {
+        let mut editor = Editor::new(Catalog::new(locales(
+            json!({ "a": "A", "n": 1 }),
+            json!({}),
+        )));
+
+        editor.set_value(&key("a"), 1, "Ä").unwrap();
+        editor.set_value(&key("a"), 0, "AA").unwrap();
+        editor.set_value(&key("n"), 0, "2").unwrap();
+
+        assert_eq!(
+            editor.catalog.json("en").unwrap(),
+            &json!({ "a": "AA", "n": 2 })
+        );
+        assert_eq!(editor.catalog.json("sv").unwrap(), &json!({ "a": "Ä" }));
+        assert_eq!(editor.catalog.changed().collect::<Vec<_>>(), ["en", "sv"]);
+    }
+
+    #[test]
+    fn should_add_rename_and_delete_keys_in_every_locale() {
+        let mut editor = Editor::new(Catalog::new(locales(
+            json!({ "a": "A" }),
+            json!({ "a": "Ä" }),
+        )));
+
+        editor.add_key(&key("pax.rooms")).unwrap();
+        editor.rename_key(&key("a"), &key("b")).unwrap();
+        editor.delete_key(&key("pax")).unwrap();
+
+        assert_eq!(editor.catalog.json("en").unwrap(), &json!({ "b": "A" }));
+        assert_eq!(editor.catalog.json("sv").unwrap(), &json!({ "b": "Ä" }));
+    }
+
+    #[test]
+    fn should_return_err_and_keep_locales_if_added_key_exists() {
+        let mut editor = Editor::new(Catalog::new(locales(json!({ "a": "A" }), json!({}))));
+
+        let result = editor.add_key(&key("a"));
+
+        assert_eq!(result.unwrap_err(), "Key 'a' already exists!");
+        assert_eq!(editor.catalog.json("sv").unwrap(), &json!({}));
+    }
+}
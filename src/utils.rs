@@ -8,8 +8,8 @@ pub fn parse_limit(input: &str) -> Result<Vec<String>, String> {
         .collect()
 }
 
-pub fn validate_required_keys_exist(
-    updates: &HashMap<String, String>,
+pub fn validate_required_keys_exist<V>(
+    updates: &HashMap<String, V>,
     paths: &[PathBuf],
     required_keys: &[String],
 ) -> Result<(), String> {
@@ -49,8 +49,8 @@ pub fn validate_required_keys_exist(
     Ok(())
 }
 
-pub fn validate_paths_and_updates_file_keys_match(
-    updates: &HashMap<String, String>,
+pub fn validate_paths_and_updates_file_keys_match<V>(
+    updates: &HashMap<String, V>,
     paths: &[PathBuf],
 ) -> Result<(), String> {
     let mut path_keys = HashSet::new();
@@ -108,17 +108,6 @@ pub fn get_file_stem<P: AsRef<Path>>(path_str: P) -> Result<String, String> {
         .ok_or_else(|| "Can't get file stem!".to_string())
 }
 
-pub fn get_path_and_key(s: &str) -> Result<(Vec<&str>, &str), String> {
-    match s.rfind('.') {
-        Some(pos) => {
-            let (left, right) = s.split_at(pos);
-            let path_parts: Vec<&str> = left.split('.').collect();
-            Ok((path_parts, &right[1..]))
-        }
-        None => Ok((vec![], s)),
-    }
-}
-
 #[cfg(test)]
 mod validate_paths_and_updates_file_keys_match {
     use super::*;
@@ -0,0 +1,363 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use ignore::WalkBuilder;
+use notify::{RecursiveMode, Watcher};
+use serde_json::Value;
+
+use crate::catalog::default_reference;
+use crate::check::{Problem, check_locales};
+use crate::files;
+use crate::json::key_path::KeyPath;
+use crate::json::lookup::get_value_at_path;
+use crate::json::paths::get_json_paths;
+
+/// How long to wait for more events after the first one, editors often
+/// write a file in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Locale files, source files and problems kept in memory, so a change only
+/// re-reads the files it touched and re-checks the keys it affected.
+struct Workspace {
+    locale_dir: PathBuf,
+    source_dir: PathBuf,
+    /// Locale files by path relative to current directory, sorted.
+    locales: Vec<(PathBuf, Value)>,
+    sources: BTreeMap<PathBuf, String>,
+    /// Number of keys in every locale file, to pick the reference one.
+    key_counts: BTreeMap<PathBuf, usize>,
+    /// Locale file placeholders are compared with.
+    reference: Option<PathBuf>,
+    /// Keys of locale files mentioned in every source file.
+    usages: BTreeMap<PathBuf, BTreeSet<KeyPath>>,
+    problems: BTreeSet<Problem>,
+}
+
+impl Workspace {
+    fn load(locale_dir: PathBuf, source_dir: PathBuf) -> Result<Self, String> {
+        let mut workspace = Workspace {
+            locale_dir,
+            source_dir,
+            locales: Vec::new(),
+            sources: BTreeMap::new(),
+            key_counts: BTreeMap::new(),
+            reference: None,
+            usages: BTreeMap::new(),
+            problems: BTreeSet::new(),
+        };
+
+        let locale_files = files::list_json_files_in_dir(&workspace.locale_dir)
+            .map_err(|e| format!("Can't list {:?}: {}", workspace.locale_dir, e))?;
+        let mut keys = BTreeSet::new();
+        for file in locale_files {
+            keys.extend(workspace.update(&file));
+        }
+
+        let walker = WalkBuilder::new(&workspace.source_dir).build();
+        for entry in walker.flatten() {
+            if entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
+                keys.extend(workspace.update(entry.path()));
+            }
+        }
+
+        workspace.recheck(keys);
+        Ok(workspace)
+    }
+
+    /// Re-reads changed file, or forgets it if it was removed. Returns keys
+    /// whose problems may have changed.
+    fn update(&mut self, file: &Path) -> BTreeSet<KeyPath> {
+        if file.parent() == Some(&self.locale_dir) {
+            if !files::is_json_file(file) {
+                return BTreeSet::new();
+            }
+            let name = PathBuf::from(display(file));
+            let position = self.locales.iter().position(|(known, _)| *known == name);
+            let new_json = if file.exists() {
+                match files::load_json_into_value(&file) {
+                    Ok(json) => Some(json),
+                    // Keep last valid version, the file is likely being edited.
+                    Err(e) => {
+                        println!(
+                            "Can't parse {}, keeping previous version: {}",
+                            display(file),
+                            e
+                        );
+                        return BTreeSet::new();
+                    }
+                }
+            } else {
+                None
+            };
+
+            let old_json = match (position, new_json.clone()) {
+                (Some(i), Some(json)) => Some(std::mem::replace(&mut self.locales[i].1, json)),
+                (Some(i), None) => Some(self.locales.remove(i).1),
+                (None, Some(json)) => {
+                    self.locales.push((name.clone(), json));
+                    self.locales.sort_by(|a, b| a.0.cmp(&b.0));
+                    None
+                }
+                (None, None) => None,
+            };
+            let keys = changed_keys(old_json.as_ref(), new_json.as_ref());
+            match &new_json {
+                Some(json) => self.key_counts.insert(name, get_json_paths(json).len()),
+                None => self.key_counts.remove(&name),
+            };
+
+            for (source, content) in &self.sources {
+                let usages = self.usages.entry(source.clone()).or_default();
+                for key in &keys {
+                    if content.contains(&key.to_dotted()) {
+                        usages.insert(key.clone());
+                    } else {
+                        usages.remove(key);
+                    }
+                }
+            }
+            keys
+        } else if file.starts_with(&self.source_dir) {
+            let old_usages = self.usages.remove(file).unwrap_or_default();
+            let new_usages = match fs::read_to_string(file) {
+                Ok(content) => {
+                    let usages = used_keys(&self.paths(), &content);
+                    self.sources.insert(file.to_path_buf(), content);
+                    self.usages.insert(file.to_path_buf(), usages.clone());
+                    usages
+                }
+                Err(_) => {
+                    self.sources.remove(file);
+                    BTreeSet::new()
+                }
+            };
+            old_usages
+                .symmetric_difference(&new_usages)
+                .cloned()
+                .collect()
+        } else {
+            BTreeSet::new()
+        }
+    }
+
+    fn paths(&self) -> BTreeSet<KeyPath> {
+        self.locales
+            .iter()
+            .flat_map(|(_, json)| get_json_paths(json))
+            .collect()
+    }
+
+    /// Replaces problems of given keys with freshly found ones.
+    fn recheck(&mut self, mut keys: BTreeSet<KeyPath>) {
+        // Placeholders of every key are compared with the reference.
+        let counts = self.locales.iter().map(|(file, _)| self.key_counts[file]);
+        let reference = default_reference(counts).map(|i| self.locales[i].0.clone());
+        if reference != self.reference {
+            self.reference = reference;
+            keys.extend(self.paths());
+        }
+
+        self.problems
+            .retain(|problem| !keys.contains(&problem.path));
+        self.problems.extend(check_locales(
+            &self.locales,
+            self.reference.as_deref(),
+            Some(&keys),
+        ));
+
+        let locale_dir = PathBuf::from(display(&self.locale_dir));
+        for key in &keys {
+            let is_known = self
+                .locales
+                .iter()
+                .any(|(_, json)| get_value_at_path(json, &key.segments()).is_some());
+            if is_known && !self.usages.values().any(|usages| usages.contains(key)) {
+                self.problems.insert(Problem {
+                    file: locale_dir.clone(),
+                    path: key.clone(),
+                    message: format!("not used in {}", display(&self.source_dir)),
+                });
+            }
+        }
+    }
+}
+
+/// Watches locale directory and source directory, printing problems that
+/// appear or get resolved after every change.
+pub fn watch(where_: &str, source: &str) -> Result<(), String> {
+    let locale_dir =
+        fs::canonicalize(where_).map_err(|e| format!("Can't open {}: {}", where_, e))?;
+    let source_dir =
+        fs::canonicalize(source).map_err(|e| format!("Can't open {}: {}", source, e))?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(|e| e.to_string())?;
+    watcher
+        .watch(&locale_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+    watcher
+        .watch(&source_dir, RecursiveMode::Recursive)
+        .map_err(|e| e.to_string())?;
+
+    let mut workspace = Workspace::load(locale_dir, source_dir)?;
+    print_changes(&BTreeSet::new(), &workspace.problems);
+    println!("\nWatching {} and {} for changes...", where_, source);
+
+    while let Ok(event) = receiver.recv() {
+        let mut changed = BTreeSet::new();
+        let mut next = Some(event);
+        while let Some(event) = next {
+            // Reading files triggers access events, those must not cause another check.
+            if let Ok(event) = event
+                && (event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove())
+            {
+                changed.extend(event.paths);
+            }
+            next = receiver.recv_timeout(DEBOUNCE).ok();
+        }
+
+        if changed.is_empty() {
+            continue;
+        }
+        let mut keys = BTreeSet::new();
+        for file in &changed {
+            keys.extend(workspace.update(file));
+        }
+        if keys.is_empty() {
+            continue;
+        }
+
+        let problems = workspace.problems.clone();
+        workspace.recheck(keys);
+        if workspace.problems != problems {
+            println!();
+            print_changes(&problems, &workspace.problems);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_changes(old: &BTreeSet<Problem>, new: &BTreeSet<Problem>) {
+    for problem in new.difference(old) {
+        println!("+ {}", problem);
+    }
+    for problem in old.difference(new) {
+        println!("- {}", problem);
+    }
+    if new.is_empty() {
+        println!("No problems found.");
+    }
+}
+
+/// Returns paths mentioned in source, the way `list-unused-keys` looks for
+/// them.
+fn used_keys(paths: &BTreeSet<KeyPath>, content: &str) -> BTreeSet<KeyPath> {
+    paths
+        .iter()
+        .filter(|path| content.contains(&path.to_dotted()))
+        .cloned()
+        .collect()
+}
+
+/// Keys present in either version of a file whose value differs between them.
+fn changed_keys(old: Option<&Value>, new: Option<&Value>) -> BTreeSet<KeyPath> {
+    let paths = |json: Option<&Value>| json.map(get_json_paths).unwrap_or_default();
+    paths(old)
+        .into_iter()
+        .chain(paths(new))
+        .filter(|path| {
+            let segments = path.segments();
+            let old = old.and_then(|json| get_value_at_path(json, &segments));
+            let new = new.and_then(|json| get_value_at_path(json, &segments));
+            old != new
+        })
+        .collect()
+}
+
+/// Shows path relative to current directory when possible.
+fn display(path: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|current_dir| path.strip_prefix(current_dir).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod used_keys {
+    use super::*;
+
+    #[test]
+    fn should_return_paths_mentioned_in_source() {
+        let paths = BTreeSet::from([
+            KeyPath::parse("pax.rooms").unwrap(),
+            KeyPath::parse("pax.adults").unwrap(),
+        ]);
+
+        let result = used_keys(&paths, "t('pax.rooms')");
+
+        assert_eq!(
+            result,
+            BTreeSet::from([KeyPath::parse("pax.rooms").unwrap()])
+        );
+    }
+}
+
+#[cfg(test)]
+mod changed_keys {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_return_added_removed_and_changed_keys() {
+        let old = json!({ "a": "A", "b": "B", "c": "C" });
+        let new = json!({ "a": "A", "b": "Bee", "d": "D" });
+
+        let result = changed_keys(Some(&old), Some(&new));
+
+        assert_eq!(
+            result.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["b", "c", "d"]
+        );
+    }
+}
+
+#[cfg(test)]
+mod workspace {
+    use super::*;
+    use crate::test_support::{messages, temp_dir};
+    use serde_json::json;
+
+    #[test]
+    fn should_recheck_only_keys_changed_in_file() {
+        let dir = temp_dir("watch-workspace");
+        let lang = dir.join("lang");
+        let src = dir.join("src");
+        fs::create_dir_all(&lang).unwrap();
+        fs::create_dir_all(&src).unwrap();
+        let write = |path: &Path, json: Value| fs::write(path, json.to_string()).unwrap();
+        write(&lang.join("en.json"), json!({ "a": "A", "b": "B" }));
+        write(&lang.join("sv.json"), json!({ "a": "A" }));
+        fs::write(src.join("app.js"), "t('a'); t('b');").unwrap();
+        let mut workspace = Workspace::load(lang.clone(), src).unwrap();
+        assert_eq!(workspace.problems.len(), 1);
+
+        write(&lang.join("sv.json"), json!({ "a": "A", "b": "B" }));
+        let keys = workspace.update(&lang.join("sv.json"));
+        workspace.recheck(keys.clone());
+
+        assert_eq!(keys, BTreeSet::from([KeyPath::parse("b").unwrap()]));
+        assert_eq!(
+            messages(workspace.problems.into_iter().collect()),
+            Vec::<String>::new()
+        );
+    }
+}